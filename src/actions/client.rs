@@ -0,0 +1,85 @@
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// HTTP client used for calling action targets. Create it once and
+/// share it between calls, so connections are reused. Cloning is cheap
+/// and clones share the same connection pool, which allows adding
+/// per-call headers such as the calling user's identity:
+///
+/// ```ignore
+/// let client = shared_client.clone().header(name, user_token);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ChartExtActionClient {
+    client: reqwest::Client,
+    timeout: Option<Duration>,
+    headers: HeaderMap,
+}
+
+impl ChartExtActionClient {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            timeout: None,
+            headers: HeaderMap::new(),
+        }
+    }
+
+    /// Timeout for targets that don't set their own `timeout_secs`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Header sent with every request, replacing previous values
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Client shared by calls not passing their own, created on first use
+    pub(crate) fn shared() -> &'static Self {
+        static SHARED: OnceLock<ChartExtActionClient> = OnceLock::new();
+        SHARED.get_or_init(Self::default)
+    }
+
+    pub(crate) fn request(
+        &self,
+        method: reqwest::Method,
+        url: url::Url,
+        timeout: Option<Duration>,
+    ) -> reqwest::RequestBuilder {
+        let request = self
+            .client
+            .request(method, url)
+            .headers(self.headers.clone());
+        match timeout.or(self.timeout) {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+}
+
+/// Response of an action target, regardless of its status code
+#[derive(Clone, Debug)]
+pub struct ChartExtActionResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl ChartExtActionResponse {
+    pub(crate) async fn from_response(response: reqwest::Response) -> Result<Self, reqwest::Error> {
+        Ok(Self {
+            status: response.status(),
+            headers: response.headers().clone(),
+            body: response.text().await?,
+        })
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+}
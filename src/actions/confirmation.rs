@@ -0,0 +1,54 @@
+use super::template::ChartExtDeploymentContext;
+use super::v0::ChartExtActionV0;
+use crate::localization::LocalizedString;
+use serde::{Deserialize, Serialize};
+
+/// Text the user has to type before running an action:
+///
+/// ```yaml
+/// confirmation:
+///   expected: deployment_name
+///   prompt: Type the deployment name to stop all workers
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ChartExtActionConfirmation {
+    pub expected: ChartExtActionConfirmationText,
+    pub prompt: Option<LocalizedString>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ChartExtActionConfirmationText {
+    /// The name of the deployment the action is called on
+    DeploymentName,
+    /// A fixed text, e.g. `DELETE`
+    Text(String),
+}
+
+impl ChartExtActionConfirmation {
+    pub fn expected_text(&self, deployment: &ChartExtDeploymentContext) -> String {
+        match &self.expected {
+            ChartExtActionConfirmationText::DeploymentName => deployment.name.clone(),
+            ChartExtActionConfirmationText::Text(text) => text.clone(),
+        }
+    }
+}
+
+impl ChartExtActionV0 {
+    /// Check the text typed by the user matches the action's
+    /// confirmation. Always true for actions without a confirmation.
+    pub fn is_confirmed(
+        &self,
+        typed: Option<&str>,
+        deployment: &ChartExtDeploymentContext,
+    ) -> bool {
+        match self.confirmation.as_ref() {
+            None => true,
+            Some(confirmation) => {
+                typed.is_some_and(|typed| typed.trim() == confirmation.expected_text(deployment))
+            }
+        }
+    }
+}
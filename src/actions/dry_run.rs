@@ -0,0 +1,96 @@
+use super::client::ChartExtActionClient;
+use super::response::ChartExtActionResult;
+use super::template::ChartExtDeploymentContext;
+use super::v0::{ChartExtActionTarget, ChartExtActionTargetResolver, ChartExtActionV0};
+use crate::error::{ChartExtActionResponseError, ChartExtActionTemplateError};
+use reqwest::header::{HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+
+/// How to run an action without applying its changes, to show what it
+/// would do:
+///
+/// ```yaml
+/// dry_run:
+///   header:
+///     name: X-Dry-Run
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ChartExtActionDryRun {
+    /// Call a different target with the same body
+    Target(ChartExtActionTarget),
+    /// Call the action's target with this header added. This calls the
+    /// real target, so it relies on the server honouring the header: a
+    /// server ignoring it applies the action. Only use it for targets
+    /// known to support the header.
+    Header {
+        name: String,
+        #[serde(default = "default_header_value")]
+        value: String,
+    },
+}
+
+fn default_header_value() -> String {
+    "true".to_owned()
+}
+
+impl ChartExtActionDryRun {
+    /// The header to add for `Header` dry runs, failing if its name or
+    /// value are not valid in a header
+    pub fn header(&self) -> Result<Option<(HeaderName, HeaderValue)>, ChartExtActionResponseError> {
+        let Self::Header { name, value } = self else {
+            return Ok(None);
+        };
+        let invalid_header = || ChartExtActionResponseError::InvalidDryRunHeader(name.clone());
+        Ok(Some((
+            HeaderName::try_from(name.as_str()).map_err(|_| invalid_header())?,
+            HeaderValue::try_from(value.as_str()).map_err(|_| invalid_header())?,
+        )))
+    }
+}
+
+impl ChartExtActionV0 {
+    /// Call the action's dry run variant, rendering its target's path
+    /// the same way as the action's. Its response is parsed the same way
+    /// as the action's, see `call_with`.
+    pub async fn dry_run_with<R, T, E>(
+        &self,
+        client: &ChartExtActionClient,
+        resolver: &R,
+        deployment: &ChartExtDeploymentContext,
+        inputs: &serde_json::Value,
+        body: T,
+    ) -> Result<ChartExtActionResult, E>
+    where
+        R: ChartExtActionTargetResolver,
+        T: serde::Serialize,
+        E: From<R::Error>
+            + From<reqwest::Error>
+            + From<ChartExtActionResponseError>
+            + From<ChartExtActionTemplateError>,
+    {
+        let response = match self.dry_run.as_ref() {
+            None => return Err(ChartExtActionResponseError::NoDryRun.into()),
+            Some(ChartExtActionDryRun::Target(target)) => {
+                target
+                    .render(inputs, deployment)?
+                    .call_with::<R, T, E>(client, resolver, body)
+                    .await?
+            }
+            Some(dry_run @ ChartExtActionDryRun::Header { .. }) => {
+                let client = match dry_run.header()? {
+                    Some((name, value)) => client.clone().header(name, value),
+                    None => client.clone(),
+                };
+                self.render_target(inputs, deployment)?
+                    .call_with::<R, T, E>(&client, resolver, body)
+                    .await?
+            }
+        };
+        if !response.is_success() {
+            return Err(ChartExtActionResponseError::Status(response.status, response.body).into());
+        }
+        Ok(self.parse_response(&response.body)?)
+    }
+}
@@ -0,0 +1,174 @@
+use super::client::ChartExtActionClient;
+use super::template::ChartExtDeploymentContext;
+use super::v0::{ChartExtActionBodyEncoding, ChartExtActionTarget, ChartExtActionTargetResolver};
+use crate::error::{ChartExtActionResponseError, ChartExtActionTemplateError};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Makes an action long-running: instead of the action's result, its
+/// target returns a `ChartExtActionJob` which is then polled until done.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ChartExtActionLongRunning {
+    /// Target returning a `ChartExtActionJobStatus`. Its path is rendered
+    /// like the action's, with `{job_id}` filled with the job ID.
+    pub poll: ChartExtActionTarget,
+    /// At least `MIN_POLL_INTERVAL_SECS`, smaller values are raised to it
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// Stop waiting for the job after this long
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl ChartExtActionLongRunning {
+    pub const MIN_POLL_INTERVAL_SECS: u64 = 1;
+    /// Name of the poll path variable filled with the job ID
+    pub const JOB_ID_VARIABLE: &'static str = "job_id";
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval_secs.max(Self::MIN_POLL_INTERVAL_SECS))
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+}
+
+fn default_poll_interval_secs() -> u64 {
+    5
+}
+
+fn default_timeout_secs() -> u64 {
+    60 * 60
+}
+
+/// Returned by the target of a long-running action when starting it
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ChartExtActionJob {
+    pub job_id: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum ChartExtActionJobState {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl ChartExtActionJobState {
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ChartExtActionJobStatus {
+    pub state: ChartExtActionJobState,
+    /// Progress in percents
+    pub progress: Option<u8>,
+    pub message: Option<String>,
+    /// Final result, once the job is done
+    pub result: Option<serde_json::Value>,
+}
+
+/// A started long-running action, see `ChartExtActionV0::start_job`
+#[derive(Clone, Debug)]
+pub struct ChartExtActionJobPoller {
+    pub job: ChartExtActionJob,
+    poll: ChartExtActionTarget,
+    long_running: ChartExtActionLongRunning,
+}
+
+impl ChartExtActionJobPoller {
+    /// Render the poll target of `long_running` for `job`, filling its
+    /// path with the action's inputs, the deployment and the job ID.
+    pub(crate) fn new(
+        job: ChartExtActionJob,
+        long_running: ChartExtActionLongRunning,
+        inputs: &serde_json::Value,
+        deployment: &ChartExtDeploymentContext,
+    ) -> Result<Self, ChartExtActionTemplateError> {
+        let mut inputs = match inputs {
+            serde_json::Value::Object(inputs) => inputs.clone(),
+            _ => Default::default(),
+        };
+        inputs.insert(
+            ChartExtActionLongRunning::JOB_ID_VARIABLE.to_owned(),
+            serde_json::Value::String(job.job_id.clone()),
+        );
+        let mut poll = long_running
+            .poll
+            .render(&serde_json::Value::Object(inputs), deployment)?;
+        // Polls have no body to send
+        poll.body_encoding
+            .get_or_insert(ChartExtActionBodyEncoding::None);
+        Ok(Self {
+            job,
+            poll,
+            long_running,
+        })
+    }
+
+    /// The poll target with its path rendered
+    pub fn target(&self) -> &ChartExtActionTarget {
+        &self.poll
+    }
+
+    /// Get the current status of the job
+    pub async fn poll<R, E>(
+        &self,
+        client: &ChartExtActionClient,
+        resolver: &R,
+    ) -> Result<ChartExtActionJobStatus, E>
+    where
+        R: ChartExtActionTargetResolver,
+        E: From<R::Error> + From<reqwest::Error> + From<ChartExtActionResponseError>,
+    {
+        let response = self
+            .target()
+            .call_with::<R, _, E>(client, resolver, serde_json::Value::Null)
+            .await?;
+        if !response.is_success() {
+            return Err(ChartExtActionResponseError::Status(response.status, response.body).into());
+        }
+        Ok(
+            serde_json::from_str(&response.body)
+                .map_err(ChartExtActionResponseError::InvalidJob)?,
+        )
+    }
+
+    /// Poll the job every `poll_interval` until it reaches a terminal
+    /// state or `timeout` passes, calling `on_progress` with every status received.
+    pub async fn wait<R, E, F>(
+        &self,
+        client: &ChartExtActionClient,
+        resolver: &R,
+        mut on_progress: F,
+    ) -> Result<ChartExtActionJobStatus, E>
+    where
+        R: ChartExtActionTargetResolver,
+        E: From<R::Error> + From<reqwest::Error> + From<ChartExtActionResponseError>,
+        F: FnMut(&ChartExtActionJobStatus),
+    {
+        let started = Instant::now();
+        let timeout = self.long_running.timeout();
+        loop {
+            let status = self.poll::<R, E>(client, resolver).await?;
+            on_progress(&status);
+            if status.state.is_terminal() {
+                return Ok(status);
+            }
+            if started.elapsed() >= timeout {
+                return Err(
+                    ChartExtActionResponseError::JobTimeout(self.job.job_id.clone()).into(),
+                );
+            }
+            tokio::time::sleep(self.long_running.poll_interval()).await;
+        }
+    }
+}
@@ -1,10 +1,31 @@
+mod client;
+mod confirmation;
+mod dry_run;
+mod job;
+mod response;
+mod retry;
+mod template;
 mod v0;
 mod v1beta1;
 
+pub use self::client::{ChartExtActionClient, ChartExtActionResponse};
+pub use self::confirmation::{ChartExtActionConfirmation, ChartExtActionConfirmationText};
+pub use self::dry_run::ChartExtActionDryRun;
+pub use self::job::{
+    ChartExtActionJob, ChartExtActionJobPoller, ChartExtActionJobState, ChartExtActionJobStatus,
+    ChartExtActionLongRunning,
+};
+pub use self::response::{
+    ChartExtActionNotice, ChartExtActionNoticeSeverity, ChartExtActionResponseField,
+    ChartExtActionResponseFormat, ChartExtActionResult,
+};
+pub use self::retry::{ChartExtActionRetryPolicy, IDEMPOTENCY_KEY_HEADER};
+pub use self::template::ChartExtDeploymentContext;
 pub use self::v0::{
-    ChartExtActionEndpoint, ChartExtActionMethod, ChartExtActionTarget,
-    ChartExtActionTargetResolver, ChartExtActionUserDeploymentRole, ChartExtActionV0,
-    ChartExtActionsV0,
+    ChartExtActionBodyEncoding, ChartExtActionDenialReason, ChartExtActionEndpoint,
+    ChartExtActionEndpointScheme, ChartExtActionMethod, ChartExtActionPermission,
+    ChartExtActionServiceEndpoint, ChartExtActionTarget, ChartExtActionTargetResolver,
+    ChartExtActionUserDeploymentRole, ChartExtActionV0, ChartExtActionsV0,
 };
 pub use self::v1beta1::{ChartExtActionV1Beta1, ChartExtActionsV1Beta1};
 use serde::{Deserialize, Serialize};
@@ -31,4 +52,11 @@ impl ChartExtActions {
             Self::V0(v0) => v0.get_actions(),
         }
     }
+
+    pub fn localize(&mut self, locale: &str) {
+        match self {
+            Self::V1Beta1(v1) => v1.localize(locale),
+            Self::V0(v0) => v0.localize(locale),
+        }
+    }
 }
@@ -0,0 +1,100 @@
+use crate::collection::UiSchemaCollectionPropertyType;
+use crate::error::ChartExtActionResponseError;
+use serde::{Deserialize, Serialize};
+
+/// How to interpret the body returned by an action's target:
+///
+/// ```yaml
+/// response:
+///   type: Json
+///   schema:
+///     - name: reindexed
+///       type: Number
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(tag = "type")]
+pub enum ChartExtActionResponseFormat {
+    #[default]
+    Text,
+    /// A JSON value. When a schema is set, the value must be an object
+    /// containing these fields.
+    Json {
+        #[serde(default)]
+        schema: Vec<ChartExtActionResponseField>,
+    },
+    /// A `ChartExtActionNotice` object to display to the user
+    Notice,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ChartExtActionResponseField {
+    pub name: String,
+    /// The JSON type of the field's value
+    #[serde(rename = "type")]
+    pub field_type: UiSchemaCollectionPropertyType,
+    #[serde(default)]
+    pub optional: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum ChartExtActionNoticeSeverity {
+    Info,
+    Success,
+    Warning,
+    Danger,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ChartExtActionNotice {
+    pub severity: ChartExtActionNoticeSeverity,
+    pub message: String,
+}
+
+/// Result of an action, parsed according to its response format
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum ChartExtActionResult {
+    Text(String),
+    Json(serde_json::Value),
+    Notice(ChartExtActionNotice),
+}
+
+impl ChartExtActionResponseFormat {
+    pub fn parse(&self, body: &str) -> Result<ChartExtActionResult, ChartExtActionResponseError> {
+        match self {
+            Self::Text => Ok(ChartExtActionResult::Text(body.to_owned())),
+            Self::Json { schema } => {
+                let value: serde_json::Value =
+                    serde_json::from_str(body).map_err(ChartExtActionResponseError::InvalidJson)?;
+                if !schema.is_empty() && !value.is_object() {
+                    return Err(ChartExtActionResponseError::NotAnObject(value));
+                }
+                for field in schema.iter() {
+                    match value.get(&field.name) {
+                        None | Some(serde_json::Value::Null) if field.optional => (),
+                        None => {
+                            return Err(ChartExtActionResponseError::MissingField(
+                                field.name.clone(),
+                            ))
+                        }
+                        Some(field_value) if !field.field_type.matches(field_value) => {
+                            return Err(ChartExtActionResponseError::InvalidFieldType(
+                                field.name.clone(),
+                                field.field_type,
+                            ))
+                        }
+                        Some(_) => (),
+                    }
+                }
+                Ok(ChartExtActionResult::Json(value))
+            }
+            Self::Notice => serde_json::from_str(body)
+                .map(ChartExtActionResult::Notice)
+                .map_err(ChartExtActionResponseError::InvalidNotice),
+        }
+    }
+}
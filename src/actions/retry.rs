@@ -0,0 +1,88 @@
+use super::v0::ChartExtActionMethod;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Header sent with every action call, unique per call and identical
+/// across its retries, so targets can avoid running an action twice.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// When to repeat a failed action call. By default only calls using
+/// idempotent methods (GET, PUT, DELETE) are retried.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ChartExtActionRetryPolicy {
+    /// Total number of attempts, including the first one
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Time to wait before the first retry
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    /// The wait time is multiplied by this after each retry
+    #[serde(default = "default_backoff_multiplier")]
+    pub backoff_multiplier: u32,
+    /// Responses with these status codes are retried, as well as
+    /// connection errors and timeouts.
+    #[serde(default = "default_retryable_status_codes")]
+    pub retryable_status_codes: Vec<u16>,
+    /// Also retry POST and PATCH calls, for targets honoring the
+    /// idempotency key header.
+    #[serde(default)]
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for ChartExtActionRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            backoff_multiplier: default_backoff_multiplier(),
+            retryable_status_codes: default_retryable_status_codes(),
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl ChartExtActionRetryPolicy {
+    pub(crate) fn max_attempts(&self, method: &ChartExtActionMethod) -> u32 {
+        let idempotent = matches!(
+            method,
+            ChartExtActionMethod::Get | ChartExtActionMethod::Put | ChartExtActionMethod::Delete
+        );
+        if idempotent || self.retry_non_idempotent {
+            self.max_attempts.max(1)
+        } else {
+            1
+        }
+    }
+
+    /// Time to wait before retrying after `attempt` failed attempts
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let multiplier = u64::from(self.backoff_multiplier).saturating_pow(attempt - 1);
+        Duration::from_millis(self.initial_backoff_ms.saturating_mul(multiplier))
+    }
+
+    pub(crate) fn should_retry(&self, result: &Result<reqwest::Response, reqwest::Error>) -> bool {
+        match result {
+            Ok(response) => self
+                .retryable_status_codes
+                .contains(&response.status().as_u16()),
+            Err(err) => err.is_connect() || err.is_timeout(),
+        }
+    }
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    500
+}
+
+fn default_backoff_multiplier() -> u32 {
+    2
+}
+
+fn default_retryable_status_codes() -> Vec<u16> {
+    vec![502, 503, 504]
+}
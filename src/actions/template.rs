@@ -0,0 +1,267 @@
+use super::dry_run::ChartExtActionDryRun;
+use super::job::ChartExtActionLongRunning;
+use super::v0::{ChartExtActionEndpoint, ChartExtActionTarget, ChartExtActionV0};
+use crate::error::ChartExtActionTemplateError;
+use url::Url;
+use uuid::Uuid;
+
+/// The deployment an action is called on, available to path and URL
+/// templates as `{deployment.id}`, `{deployment.name}`,
+/// `{deployment.namespace}` and `{deployment.env_id}`.
+#[derive(Clone, Debug)]
+pub struct ChartExtDeploymentContext {
+    pub id: Uuid,
+    pub name: String,
+    pub namespace: String,
+    pub env_id: Uuid,
+}
+
+impl ChartExtDeploymentContext {
+    const FIELDS: [&'static str; 4] = ["id", "name", "namespace", "env_id"];
+
+    fn get(&self, field: &str) -> Option<String> {
+        match field {
+            "id" => Some(self.id.to_string()),
+            "name" => Some(self.name.clone()),
+            "namespace" => Some(self.namespace.clone()),
+            "env_id" => Some(self.env_id.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Render the base URL of a `UrlTemplate` endpoint for this
+    /// deployment, e.g. `https://admin.example.com/{deployment.namespace}`.
+    /// Only deployment context variables are available in URL templates.
+    pub fn render_url_template(&self, template: &str) -> Result<Url, ChartExtActionTemplateError> {
+        validate_url_template(template)?;
+        let url = render_template(template, &serde_json::Value::Null, self)?;
+        Url::parse(&url).map_err(|err| ChartExtActionTemplateError::InvalidUrl(url, err))
+    }
+}
+
+impl ChartExtActionTarget {
+    /// Variables referenced in the path, e.g. `shop_id` and
+    /// `deployment.name` in `/shops/{shop_id}?by={deployment.name}`
+    pub fn path_variables(&self) -> Result<Vec<&str>, ChartExtActionTemplateError> {
+        template_variables(&self.path)
+    }
+
+    /// Fill the variables in the path with action inputs and deployment
+    /// context. Values are URL-encoded for the part of the URL they're
+    /// in, i.e. as a path segment or as a query value.
+    pub fn render(
+        &self,
+        inputs: &serde_json::Value,
+        deployment: &ChartExtDeploymentContext,
+    ) -> Result<ChartExtActionTarget, ChartExtActionTemplateError> {
+        Ok(ChartExtActionTarget {
+            path: render_template(&self.path, inputs, deployment)?,
+            ..self.clone()
+        })
+    }
+}
+
+impl ChartExtActionV0 {
+    /// The action's target with its path rendered, see
+    /// `ChartExtActionTarget::render`.
+    pub fn render_target(
+        &self,
+        inputs: &serde_json::Value,
+        deployment: &ChartExtDeploymentContext,
+    ) -> Result<ChartExtActionTarget, ChartExtActionTemplateError> {
+        self.target.render(inputs, deployment)
+    }
+
+    /// Check that path variables refer to deployment context fields or
+    /// to inputs of the action's UI schema, including the paths of its
+    /// dry run target and of its poll target if it's long-running. Poll
+    /// paths may additionally refer to the job ID.
+    pub fn validate_path(&self) -> Result<(), ChartExtActionTemplateError> {
+        self.validate_target_path(&self.target, &[])?;
+        if let Some(ChartExtActionDryRun::Target(target)) = self.dry_run.as_ref() {
+            self.validate_target_path(target, &[])?;
+        }
+        if let Some(long_running) = self.long_running.as_ref() {
+            self.validate_target_path(
+                &long_running.poll,
+                &[ChartExtActionLongRunning::JOB_ID_VARIABLE],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Check the path of `target`, which is rendered with the action's
+    /// inputs and the `extra` variables.
+    fn validate_target_path(
+        &self,
+        target: &ChartExtActionTarget,
+        extra: &[&str],
+    ) -> Result<(), ChartExtActionTemplateError> {
+        if let ChartExtActionEndpoint::UrlTemplate(template) = &target.endpoint {
+            validate_url_template(template)?;
+        }
+        for name in target.path_variables()? {
+            let known = match name.strip_prefix("deployment.") {
+                Some(field) => ChartExtDeploymentContext::FIELDS.contains(&field),
+                None => {
+                    extra.contains(&name)
+                        || self.ui_schema.as_ref().is_some_and(|ui_schema| {
+                            ui_schema.get_inputs().iter().any(|input| input.id == name)
+                        })
+                }
+            };
+            if !known {
+                return Err(ChartExtActionTemplateError::UnknownVariable(
+                    name.to_owned(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+enum TemplatePart<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+}
+
+fn template_variables(template: &str) -> Result<Vec<&str>, ChartExtActionTemplateError> {
+    Ok(parse_template(template)?
+        .into_iter()
+        .filter_map(|part| match part {
+            TemplatePart::Variable(name) => Some(name),
+            TemplatePart::Text(_) => None,
+        })
+        .collect())
+}
+
+/// Check that a URL template only refers to deployment context fields
+fn validate_url_template(template: &str) -> Result<(), ChartExtActionTemplateError> {
+    match template_variables(template)?.into_iter().find(|name| {
+        !name
+            .strip_prefix("deployment.")
+            .is_some_and(|field| ChartExtDeploymentContext::FIELDS.contains(&field))
+    }) {
+        Some(name) => Err(ChartExtActionTemplateError::UnknownVariable(
+            name.to_owned(),
+        )),
+        None => Ok(()),
+    }
+}
+
+fn render_template(
+    template: &str,
+    inputs: &serde_json::Value,
+    deployment: &ChartExtDeploymentContext,
+) -> Result<String, ChartExtActionTemplateError> {
+    let mut rendered = String::new();
+    let mut in_query = false;
+    // Last variable filled into the current path segment
+    let mut segment_variable = None;
+    for part in parse_template(template)? {
+        match part {
+            TemplatePart::Text(text) => {
+                if !in_query {
+                    let segment_end = text.find(['/', '?']).unwrap_or(text.len());
+                    if segment_end < text.len() {
+                        check_segment(&rendered, &text[..segment_end], segment_variable.take())?;
+                    }
+                    in_query = text.contains('?');
+                }
+                rendered.push_str(text);
+            }
+            TemplatePart::Variable(name) => {
+                let value = variable_value(name, inputs, deployment)?;
+                if in_query {
+                    rendered.extend(url::form_urlencoded::byte_serialize(value.as_bytes()));
+                } else {
+                    rendered.push_str(&encode_path_segment(&value));
+                    segment_variable = Some((name, value));
+                }
+            }
+        }
+    }
+    if !in_query {
+        check_segment(&rendered, "", segment_variable)?;
+    }
+    Ok(rendered)
+}
+
+fn parse_template(template: &str) -> Result<Vec<TemplatePart<'_>>, ChartExtActionTemplateError> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            parts.push(TemplatePart::Text(&rest[..start]));
+        }
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| ChartExtActionTemplateError::Unclosed(template.to_owned()))?;
+        parts.push(TemplatePart::Variable(rest[start + 1..start + end].trim()));
+        rest = &rest[start + end + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(TemplatePart::Text(rest));
+    }
+    Ok(parts)
+}
+
+fn variable_value(
+    name: &str,
+    inputs: &serde_json::Value,
+    deployment: &ChartExtDeploymentContext,
+) -> Result<String, ChartExtActionTemplateError> {
+    if let Some(field) = name.strip_prefix("deployment.") {
+        return deployment
+            .get(field)
+            .ok_or_else(|| ChartExtActionTemplateError::UnknownVariable(name.to_owned()));
+    }
+    match inputs.get(name) {
+        None | Some(serde_json::Value::Null) => {
+            Err(ChartExtActionTemplateError::MissingValue(name.to_owned()))
+        }
+        Some(serde_json::Value::String(value)) => Ok(value.clone()),
+        Some(value @ (serde_json::Value::Number(_) | serde_json::Value::Bool(_))) => {
+            Ok(value.to_string())
+        }
+        Some(value) => Err(ChartExtActionTemplateError::InvalidValue(
+            name.to_owned(),
+            value.clone(),
+        )),
+    }
+}
+
+/// Fail if variables made the last segment of `path`, continued by
+/// `rest`, a `.` or `..` segment. URL parsing resolves those even when
+/// percent-encoded, which would let values reach other endpoints.
+fn check_segment(
+    path: &str,
+    rest: &str,
+    variable: Option<(&str, String)>,
+) -> Result<(), ChartExtActionTemplateError> {
+    let Some((name, value)) = variable else {
+        return Ok(());
+    };
+    let segment = format!("{}{rest}", &path[path.rfind('/').map_or(0, |i| i + 1)..]);
+    if segment == "." || segment == ".." {
+        return Err(ChartExtActionTemplateError::InvalidValue(
+            name.to_owned(),
+            serde_json::Value::String(value),
+        ));
+    }
+    Ok(())
+}
+
+/// Percent-encode everything except unreserved characters, so values
+/// can't add path segments or start the query.
+fn encode_path_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            byte => format!("%{byte:02X}"),
+        })
+        .collect()
+}
@@ -1,5 +1,13 @@
+use super::client::{ChartExtActionClient, ChartExtActionResponse};
+use super::confirmation::ChartExtActionConfirmation;
+use super::dry_run::ChartExtActionDryRun;
+use super::job::{ChartExtActionJobPoller, ChartExtActionLongRunning};
+use super::response::{ChartExtActionResponseFormat, ChartExtActionResult};
+use super::retry::{ChartExtActionRetryPolicy, IDEMPOTENCY_KEY_HEADER};
+use super::template::ChartExtDeploymentContext;
 use crate::collection::UiSchemaCollections;
-use crate::error::UiSchemaInputError;
+use crate::error::{ChartExtActionResponseError, ChartExtActionTemplateError, UiSchemaInputError};
+use crate::localization::LocalizedString;
 use crate::ui_schema::UiSchema;
 use serde::{Deserialize, Serialize};
 use url::Url;
@@ -20,23 +28,95 @@ impl ChartExtActionsV0 {
     pub fn get_actions(&self) -> Vec<ChartExtActionV0> {
         self.actions.to_vec()
     }
+
+    pub fn localize(&mut self, locale: &str) {
+        for action in self.actions.iter_mut() {
+            action.localize(locale);
+        }
+    }
 }
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Roles a user may have on a deployment, from least to most
+/// privileged. Each role can do anything the roles before it can.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub enum ChartExtActionUserDeploymentRole {
-    Owner,
+    /// Read-only access
+    Viewer,
     Maintainer,
+    Owner,
+    /// Administrator of the deployment's environment
+    EnvironmentAdmin,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+impl ChartExtActionUserDeploymentRole {
+    /// Whether a user with `user_role` has at least this role
+    pub fn allows(&self, user_role: Self) -> bool {
+        user_role >= *self
+    }
+}
+
+/// Where an action target is served:
+///
+/// ```yaml
+/// endpoint: standard_ingress
+/// ```
+///
+/// ```yaml
+/// endpoint:
+///   service:
+///     name: api
+///     port: 8080
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
 pub enum ChartExtActionEndpoint {
-    #[serde(rename = "standard_ingress")]
+    /// The deployment's standard ingress hostname
     StandardIngress,
+    /// An in-cluster service of the deployment
+    Service(ChartExtActionServiceEndpoint),
+    /// An explicit base URL, which may refer to the deployment context,
+    /// e.g. `https://admin.example.com/{deployment.namespace}`. Resolvers
+    /// render it using `ChartExtDeploymentContext::render_url_template`.
+    UrlTemplate(String),
+    /// A named ingress host of the deployment
+    IngressHost(String),
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ChartExtActionServiceEndpoint {
+    pub name: String,
+    pub port: u16,
+    #[serde(default)]
+    pub scheme: ChartExtActionEndpointScheme,
+}
+
+impl ChartExtActionServiceEndpoint {
+    /// Base URL of the service when deployed to `namespace`
+    pub fn url(&self, namespace: &str) -> Result<Url, url::ParseError> {
+        let scheme = match self.scheme {
+            ChartExtActionEndpointScheme::Http => "http",
+            ChartExtActionEndpointScheme::Https => "https",
+        };
+        Url::parse(&format!(
+            "{scheme}://{}.{namespace}.svc.cluster.local:{}",
+            self.name, self.port
+        ))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ChartExtActionEndpointScheme {
+    #[default]
+    Http,
+    Https,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "UPPERCASE")]
 pub enum ChartExtActionMethod {
@@ -47,15 +127,42 @@ pub enum ChartExtActionMethod {
     Delete,
 }
 
+/// How the action body is sent to the target
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ChartExtActionBodyEncoding {
+    Json,
+    /// Top-level fields are added to the query string. Arrays repeat the
+    /// field for each item, nested objects are sent as JSON.
+    Query,
+    /// Same as `Query`, but sent as an `application/x-www-form-urlencoded`
+    /// body
+    Form,
+    /// The body is not sent
+    None,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChartExtActionTarget {
     pub endpoint: ChartExtActionEndpoint,
     pub path: String,
     pub method: ChartExtActionMethod,
+    /// Overrides the timeout set in `ChartExtActionClient`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<ChartExtActionRetryPolicy>,
+    /// Defaults to `json` for all methods, set it to `query` to send the
+    /// body of GET and DELETE actions in the query string
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_encoding: Option<ChartExtActionBodyEncoding>,
 }
 
 impl ChartExtActionTarget {
+    /// Call the target using a client shared by all such calls, failing
+    /// on error status codes. Use `call_with` to configure the client.
     pub async fn call<R, T, E>(&self, resolver: &R, body: T) -> Result<String, E>
     where
         R: ChartExtActionTargetResolver,
@@ -63,6 +170,118 @@ impl ChartExtActionTarget {
         E: From<R::Error> + From<reqwest::Error>,
     {
         let url = resolver.resolve(self).await?;
+        Ok(self
+            .send(ChartExtActionClient::shared(), url, &body)
+            .await?
+            .error_for_status()?
+            .text()
+            .await?)
+    }
+
+    /// Call the target using a shared `client`, returning the response
+    /// even when its status code indicates an error.
+    pub async fn call_with<R, T, E>(
+        &self,
+        client: &ChartExtActionClient,
+        resolver: &R,
+        body: T,
+    ) -> Result<ChartExtActionResponse, E>
+    where
+        R: ChartExtActionTargetResolver,
+        T: Serialize,
+        E: From<R::Error> + From<reqwest::Error>,
+    {
+        let url = resolver.resolve(self).await?;
+        let response = self.send(client, url, &body).await?;
+        Ok(ChartExtActionResponse::from_response(response).await?)
+    }
+
+    /// Send the request, retrying according to the retry policy
+    async fn send<T>(
+        &self,
+        client: &ChartExtActionClient,
+        url: Url,
+        body: &T,
+    ) -> Result<reqwest::Response, reqwest::Error>
+    where
+        T: Serialize,
+    {
+        let idempotency_key = Uuid::new_v4().to_string();
+        let max_attempts = self
+            .retry
+            .as_ref()
+            .map_or(1, |retry| retry.max_attempts(&self.method));
+        let mut attempt = 1;
+        loop {
+            let request = self
+                .request(client, url.clone())
+                .header(IDEMPOTENCY_KEY_HEADER, &idempotency_key);
+            let result = self.encode_body(request, body).send().await;
+            match self.retry.as_ref() {
+                Some(retry) if attempt < max_attempts && retry.should_retry(&result) => {
+                    tokio::time::sleep(retry.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                _ => return result,
+            }
+        }
+    }
+
+    /// The URL of this target given the base URL of its endpoint. The
+    /// path is appended to the base URL's path, and its query is added to
+    /// the base URL's query.
+    pub fn url_on(&self, base: &Url) -> Url {
+        let mut url = base.clone();
+        let (path, query) = match self.path.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (self.path.as_str(), None),
+        };
+        let full_path = format!(
+            "{}/{}",
+            url.path().trim_end_matches('/'),
+            path.trim_start_matches('/')
+        );
+        url.set_path(&full_path);
+        let merged_query = match (base.query(), query) {
+            (Some(base_query), Some(query)) if !base_query.is_empty() => {
+                Some(format!("{base_query}&{query}"))
+            }
+            (base_query, None) => base_query.map(ToOwned::to_owned),
+            (_, query) => query.map(ToOwned::to_owned),
+        };
+        url.set_query(merged_query.as_deref());
+        url
+    }
+
+    pub fn body_encoding(&self) -> ChartExtActionBodyEncoding {
+        self.body_encoding
+            .unwrap_or(ChartExtActionBodyEncoding::Json)
+    }
+
+    fn encode_body<T>(&self, request: reqwest::RequestBuilder, body: &T) -> reqwest::RequestBuilder
+    where
+        T: Serialize,
+    {
+        let encoding = self.body_encoding();
+        let pairs = match encoding {
+            ChartExtActionBodyEncoding::Json => return request.json(body),
+            ChartExtActionBodyEncoding::None => return request,
+            ChartExtActionBodyEncoding::Query | ChartExtActionBodyEncoding::Form => {
+                serde_json::to_value(body)
+                    .ok()
+                    .and_then(|value| encode_pairs(&value))
+            }
+        };
+        match (pairs, encoding) {
+            (Some(pairs), ChartExtActionBodyEncoding::Query) => request.query(&pairs),
+            (Some(pairs), _) => request.form(&pairs),
+            // Let reqwest report bodies that can't be encoded when sending
+            (None, ChartExtActionBodyEncoding::Query) => request.query(body),
+            (None, _) => request.form(body),
+        }
+    }
+
+    fn request(&self, client: &ChartExtActionClient, url: Url) -> reqwest::RequestBuilder {
         let method = match self.method {
             ChartExtActionMethod::Get => reqwest::Method::GET,
             ChartExtActionMethod::Post => reqwest::Method::POST,
@@ -70,25 +289,74 @@ impl ChartExtActionTarget {
             ChartExtActionMethod::Patch => reqwest::Method::PATCH,
             ChartExtActionMethod::Delete => reqwest::Method::DELETE,
         };
+        client.request(
+            method,
+            url,
+            self.timeout_secs.map(std::time::Duration::from_secs),
+        )
+    }
+}
 
-        Ok(reqwest::Client::new()
-            .request(method, url)
-            .json(&body)
-            .send()
-            .await?
-            .error_for_status()?
-            .text()
-            .await?)
+/// Flatten a body object to URL-encoded pairs, `None` if it's neither an
+/// object nor null.
+fn encode_pairs(body: &serde_json::Value) -> Option<Vec<(String, String)>> {
+    let fields = match body {
+        serde_json::Value::Null => return Some(Vec::new()),
+        serde_json::Value::Object(fields) => fields,
+        _ => return None,
+    };
+    let encode_value = |value: &serde_json::Value| match value {
+        serde_json::Value::String(value) => value.clone(),
+        value => value.to_string(),
+    };
+    let mut pairs = Vec::new();
+    for (key, value) in fields.iter() {
+        match value {
+            serde_json::Value::Null => (),
+            serde_json::Value::Array(items) => {
+                pairs.extend(items.iter().map(|item| (key.clone(), encode_value(item))))
+            }
+            value => pairs.push((key.clone(), encode_value(value))),
+        }
     }
+    Some(pairs)
 }
 
 pub trait ChartExtActionTargetResolver {
     type Error;
 
+    /// Get the full URL of a target. Implementations resolving the
+    /// target's endpoint to a base URL can use `ChartExtActionTarget::url_on`
+    /// to add the target's path to it.
     #[allow(async_fn_in_trait)]
     async fn resolve(&self, target: &ChartExtActionTarget) -> Result<Url, Self::Error>;
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum ChartExtActionPermission {
+    Allowed,
+    /// Allowed, but the action is dangerous or has a confirmation, so
+    /// the user should confirm before running it
+    RequiresConfirmation,
+    Denied(ChartExtActionDenialReason),
+}
+
+impl ChartExtActionPermission {
+    pub fn is_allowed(&self) -> bool {
+        !matches!(self, Self::Denied(_))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum ChartExtActionDenialReason {
+    /// The user's role doesn't allow running the action
+    Role,
+    /// The action isn't allowed on the deployment's current status
+    Status,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChartExtActionV0 {
@@ -98,15 +366,78 @@ pub struct ChartExtActionV0 {
     pub allowed_on_statuses: Vec<String>,
     #[serde(flatten)]
     pub target: ChartExtActionTarget,
-    pub title: String,
+    pub title: LocalizedString,
     pub fontawesome_icon: Option<String>,
-    pub description: String,
+    pub description: LocalizedString,
     #[serde(default)]
     pub dangerous: bool,
     pub ui_schema: Option<UiSchema>,
+    /// Defaults to `Text`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<ChartExtActionResponseFormat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub long_running: Option<ChartExtActionLongRunning>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmation: Option<ChartExtActionConfirmation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dry_run: Option<ChartExtActionDryRun>,
 }
 
 impl ChartExtActionV0 {
+    /// Replace all user facing texts with their text in `locale`
+    pub fn localize(&mut self, locale: &str) {
+        self.title.localize(locale);
+        self.description.localize(locale);
+        if let Some(prompt) = self
+            .confirmation
+            .as_mut()
+            .and_then(|confirmation| confirmation.prompt.as_mut())
+        {
+            prompt.localize(locale);
+        }
+        if let Some(ui_schema) = self.ui_schema.as_mut() {
+            ui_schema.localize(locale);
+        }
+    }
+
+    /// Parse a response body according to the action's response format
+    pub(crate) fn parse_response(
+        &self,
+        body: &str,
+    ) -> Result<ChartExtActionResult, ChartExtActionResponseError> {
+        match self.response.as_ref() {
+            Some(format) => format.parse(body),
+            None => ChartExtActionResponseFormat::default().parse(body),
+        }
+    }
+
+    /// Check whether a user with `user_role` may run this action while
+    /// the deployment has `current_status`. `None` means the status is
+    /// unknown, which only allows actions not limited to any status.
+    pub fn is_allowed(
+        &self,
+        current_status: Option<&str>,
+        user_role: ChartExtActionUserDeploymentRole,
+    ) -> ChartExtActionPermission {
+        if !self.allowed_role.allows(user_role) {
+            return ChartExtActionPermission::Denied(ChartExtActionDenialReason::Role);
+        }
+        if !self.allowed_on_statuses.is_empty()
+            && !current_status.is_some_and(|status| {
+                self.allowed_on_statuses
+                    .iter()
+                    .any(|allowed| allowed == status)
+            })
+        {
+            return ChartExtActionPermission::Denied(ChartExtActionDenialReason::Status);
+        }
+        if self.dangerous || self.confirmation.is_some() {
+            ChartExtActionPermission::RequiresConfirmation
+        } else {
+            ChartExtActionPermission::Allowed
+        }
+    }
+
     pub async fn generate_body<C>(
         &self,
         env_id: Uuid,
@@ -121,4 +452,72 @@ impl ChartExtActionV0 {
         };
         Ok(ui_schema.get_values::<C>(env_id, &inputs).await?.into())
     }
+
+    /// Call the action's target, with its path rendered from `inputs`
+    /// and `deployment`, and parse its response according to the
+    /// action's response format. Error status codes fail the call.
+    pub async fn call_with<R, T, E>(
+        &self,
+        client: &ChartExtActionClient,
+        resolver: &R,
+        deployment: &ChartExtDeploymentContext,
+        inputs: &serde_json::Value,
+        body: T,
+    ) -> Result<ChartExtActionResult, E>
+    where
+        R: ChartExtActionTargetResolver,
+        T: Serialize,
+        E: From<R::Error>
+            + From<reqwest::Error>
+            + From<ChartExtActionResponseError>
+            + From<ChartExtActionTemplateError>,
+    {
+        let response = self
+            .render_target(inputs, deployment)?
+            .call_with::<R, T, E>(client, resolver, body)
+            .await?;
+        if !response.is_success() {
+            return Err(ChartExtActionResponseError::Status(response.status, response.body).into());
+        }
+        Ok(self.parse_response(&response.body)?)
+    }
+
+    /// Start a long-running action, returning a poller for following
+    /// the job it started. The target is rendered as in `call_with`.
+    pub async fn start_job<R, T, E>(
+        &self,
+        client: &ChartExtActionClient,
+        resolver: &R,
+        deployment: &ChartExtDeploymentContext,
+        inputs: &serde_json::Value,
+        body: T,
+    ) -> Result<ChartExtActionJobPoller, E>
+    where
+        R: ChartExtActionTargetResolver,
+        T: Serialize,
+        E: From<R::Error>
+            + From<reqwest::Error>
+            + From<ChartExtActionResponseError>
+            + From<ChartExtActionTemplateError>,
+    {
+        let long_running = self
+            .long_running
+            .as_ref()
+            .ok_or(ChartExtActionResponseError::NotLongRunning)?;
+        let response = self
+            .render_target(inputs, deployment)?
+            .call_with::<R, T, E>(client, resolver, body)
+            .await?;
+        if !response.is_success() {
+            return Err(ChartExtActionResponseError::Status(response.status, response.body).into());
+        }
+        let job = serde_json::from_str(&response.body)
+            .map_err(ChartExtActionResponseError::InvalidJob)?;
+        Ok(ChartExtActionJobPoller::new(
+            job,
+            long_running.clone(),
+            inputs,
+            deployment,
+        )?)
+    }
 }
@@ -16,6 +16,12 @@ impl ChartExtActionsV1Beta1 {
             .find(|action| action.spec.id == action_id)
             .map(|action| &action.spec)
     }
+
+    pub fn localize(&mut self, locale: &str) {
+        for action in self.0.iter_mut() {
+            action.spec.localize(locale);
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -0,0 +1,505 @@
+use crate::actions::{ChartExtActionTarget, ChartExtActionUserDeploymentRole, ChartExtActionV0};
+use crate::ext_type::ChartExt;
+use crate::features::{ChartExtCardinality, ChartExtFeatures, ChartExtIngressHostnameFormat};
+use crate::resource_types::{
+    ChartExtResourceLifecycleActionV1Beta1, ChartExtResourceType, ChartExtResourceTypeV1Beta1Spec,
+};
+use crate::ui_schema::{UiSchema, UiSchemaV0};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Where a set of inputs is defined in a chart
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum ChartExtSchemaScope {
+    ValuesUi,
+    Action(String),
+    ResourceType(String),
+}
+
+impl fmt::Display for ChartExtSchemaScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ValuesUi => write!(f, "values UI"),
+            Self::Action(id) => write!(f, "action {id}"),
+            Self::ResourceType(key) => write!(f, "resource type {key}"),
+        }
+    }
+}
+
+/// A difference between two versions of a chart's extensions
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum ChartExtChange {
+    InputAdded {
+        scope: ChartExtSchemaScope,
+        input: String,
+    },
+    InputRemoved {
+        scope: ChartExtSchemaScope,
+        input: String,
+    },
+    InputRenamed {
+        scope: ChartExtSchemaScope,
+        from: String,
+        to: String,
+    },
+    InputRetyped {
+        scope: ChartExtSchemaScope,
+        input: String,
+    },
+    InputNowRequired {
+        scope: ChartExtSchemaScope,
+        input: String,
+    },
+    ActionAdded(String),
+    ActionRemoved(String),
+    /// The endpoint, path, method or body encoding of an action changed
+    ActionTargetChanged(String),
+    ActionRoleChanged {
+        action: String,
+        from: ChartExtActionUserDeploymentRole,
+        to: ChartExtActionUserDeploymentRole,
+    },
+    /// The statuses an action is allowed on changed, an empty list
+    /// allowing all statuses
+    ActionStatusesChanged {
+        action: String,
+        from: Vec<String>,
+        to: Vec<String>,
+    },
+    /// An action now starts a long running job instead of returning its
+    /// result, or the other way around
+    ActionLongRunningChanged {
+        action: String,
+        long_running: bool,
+    },
+    /// The format of an action's response changed
+    ActionResponseChanged(String),
+    ResourceTypeAdded(String),
+    ResourceTypeRemoved(String),
+    ResourceTypeGlobalChanged {
+        resource_type: String,
+        global: bool,
+    },
+    /// The target of a resource type's `create`, `update` or `delete`
+    /// lifecycle action changed, including being added or removed
+    LifecycleTargetChanged {
+        resource_type: String,
+        lifecycle_action: String,
+    },
+    LifecycleRoleChanged {
+        resource_type: String,
+        lifecycle_action: String,
+        from: ChartExtActionUserDeploymentRole,
+        to: ChartExtActionUserDeploymentRole,
+    },
+    CardinalityChanged {
+        from: ChartExtCardinality,
+        to: ChartExtCardinality,
+    },
+    IngressEnabled,
+    IngressDisabled,
+    IngressHostnameFormatChanged {
+        from: ChartExtIngressHostnameFormat,
+        to: ChartExtIngressHostnameFormat,
+    },
+    StatusEnabled,
+    StatusDisabled,
+}
+
+impl ChartExtChange {
+    /// Whether existing deployments, or automation relying on the chart,
+    /// may stop working after upgrading to the new version. Renamed
+    /// inputs are migrated for stored values and resources, but callers
+    /// of an action keep sending the old input ID. An action's target
+    /// may change before deployments are upgraded to serve it.
+    /// Narrowing the statuses an action is allowed on is breaking,
+    /// widening them is not.
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            Self::InputAdded { .. }
+            | Self::ActionAdded(_)
+            | Self::ResourceTypeAdded(_)
+            | Self::IngressEnabled
+            | Self::StatusEnabled => false,
+            Self::InputRemoved { .. }
+            | Self::InputRetyped { .. }
+            | Self::InputNowRequired { .. }
+            | Self::ActionRemoved(_)
+            | Self::ActionTargetChanged(_)
+            | Self::ActionLongRunningChanged { .. }
+            | Self::ActionResponseChanged(_)
+            | Self::ResourceTypeRemoved(_)
+            | Self::ResourceTypeGlobalChanged { .. }
+            | Self::LifecycleTargetChanged { .. }
+            | Self::IngressDisabled
+            | Self::IngressHostnameFormatChanged { .. }
+            | Self::StatusDisabled => true,
+            Self::InputRenamed { scope, .. } => matches!(scope, ChartExtSchemaScope::Action(_)),
+            Self::ActionRoleChanged { from, to, .. }
+            | Self::LifecycleRoleChanged { from, to, .. } => to > from,
+            Self::ActionStatusesChanged { from, to, .. } => {
+                !to.is_empty()
+                    && (from.is_empty() || from.iter().any(|status| !to.contains(status)))
+            }
+            Self::CardinalityChanged { to, .. } => *to == ChartExtCardinality::OnePerCluster,
+        }
+    }
+}
+
+impl fmt::Display for ChartExtChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InputAdded { scope, input } => write!(f, "Input {input} was added to {scope}"),
+            Self::InputRemoved { scope, input } => {
+                write!(f, "Input {input} was removed from {scope}")
+            }
+            Self::InputRenamed { scope, from, to } => {
+                write!(f, "Input {from} was renamed to {to} in {scope}")
+            }
+            Self::InputRetyped { scope, input } => {
+                write!(f, "Input {input} changed type in {scope}")
+            }
+            Self::InputNowRequired { scope, input } => {
+                write!(
+                    f,
+                    "Input {input} is now required without an initial value in {scope}"
+                )
+            }
+            Self::ActionAdded(id) => write!(f, "Action {id} was added"),
+            Self::ActionRemoved(id) => write!(f, "Action {id} was removed"),
+            Self::ActionTargetChanged(id) => write!(f, "Target of action {id} changed"),
+            Self::ActionRoleChanged { action, from, to } => {
+                write!(
+                    f,
+                    "Role allowed to run action {action} changed from {from:?} to {to:?}"
+                )
+            }
+            Self::ActionStatusesChanged { action, from, to } => {
+                write!(
+                    f,
+                    "Statuses action {action} is allowed on changed from {from:?} to {to:?}"
+                )
+            }
+            Self::ActionLongRunningChanged {
+                action,
+                long_running: true,
+            } => write!(f, "Action {action} is now long running"),
+            Self::ActionLongRunningChanged {
+                action,
+                long_running: false,
+            } => write!(f, "Action {action} is no longer long running"),
+            Self::ActionResponseChanged(id) => write!(f, "Response format of action {id} changed"),
+            Self::ResourceTypeAdded(key) => write!(f, "Resource type {key} was added"),
+            Self::ResourceTypeRemoved(key) => write!(f, "Resource type {key} was removed"),
+            Self::ResourceTypeGlobalChanged {
+                resource_type,
+                global: true,
+            } => write!(f, "Resource type {resource_type} is now global"),
+            Self::ResourceTypeGlobalChanged {
+                resource_type,
+                global: false,
+            } => write!(f, "Resource type {resource_type} is no longer global"),
+            Self::LifecycleTargetChanged {
+                resource_type,
+                lifecycle_action,
+            } => write!(
+                f,
+                "Target of the {lifecycle_action} action of resource type {resource_type} changed"
+            ),
+            Self::LifecycleRoleChanged {
+                resource_type,
+                lifecycle_action,
+                from,
+                to,
+            } => write!(
+                f,
+                "Role allowed to run the {lifecycle_action} action of resource type \
+                 {resource_type} changed from {from:?} to {to:?}"
+            ),
+            Self::CardinalityChanged { from, to } => {
+                write!(f, "Cardinality changed from {from:?} to {to:?}")
+            }
+            Self::IngressEnabled => write!(f, "Ingress was enabled"),
+            Self::IngressDisabled => write!(f, "Ingress was disabled"),
+            Self::IngressHostnameFormatChanged { from, to } => {
+                write!(f, "Ingress hostname format changed from {from:?} to {to:?}")
+            }
+            Self::StatusEnabled => write!(f, "Status was enabled"),
+            Self::StatusDisabled => write!(f, "Status was disabled"),
+        }
+    }
+}
+
+impl ChartExt {
+    /// Compare this chart with an older version of it. Use
+    /// `ChartExtChange::is_breaking` to find changes requiring attention,
+    /// e.g. for gating releases.
+    pub fn changes_from(&self, old: &ChartExt) -> Vec<ChartExtChange> {
+        let mut changes = Vec::new();
+
+        compare_inputs(
+            ChartExtSchemaScope::ValuesUi,
+            old.ui_schema.as_ref(),
+            self.ui_schema.as_ref(),
+            &mut changes,
+        );
+
+        let old_actions = old
+            .actions
+            .as_ref()
+            .map(|actions| actions.get_actions())
+            .unwrap_or_default();
+        let new_actions = self
+            .actions
+            .as_ref()
+            .map(|actions| actions.get_actions())
+            .unwrap_or_default();
+        for old_action in old_actions.iter() {
+            match new_actions.iter().find(|action| action.id == old_action.id) {
+                None => changes.push(ChartExtChange::ActionRemoved(old_action.id.clone())),
+                Some(new_action) => compare_action(old_action, new_action, &mut changes),
+            }
+        }
+        for new_action in new_actions
+            .iter()
+            .filter(|action| !old_actions.iter().any(|old| old.id == action.id))
+        {
+            changes.push(ChartExtChange::ActionAdded(new_action.id.clone()));
+        }
+
+        let old_resource_types = resource_type_specs(old);
+        let new_resource_types = resource_type_specs(self);
+        for (key, old_spec) in old_resource_types.iter() {
+            match new_resource_types
+                .iter()
+                .find(|(new_key, _)| new_key == key)
+            {
+                None => changes.push(ChartExtChange::ResourceTypeRemoved(key.clone())),
+                Some((_, new_spec)) => compare_resource_type(key, old_spec, new_spec, &mut changes),
+            }
+        }
+        for (key, _) in new_resource_types
+            .iter()
+            .filter(|(key, _)| !old_resource_types.iter().any(|(old_key, _)| old_key == key))
+        {
+            changes.push(ChartExtChange::ResourceTypeAdded(key.clone()));
+        }
+
+        let old_features = old.features.clone().unwrap_or_default();
+        let new_features = self.features.clone().unwrap_or_default();
+        compare_features(&old_features, &new_features, &mut changes);
+
+        changes
+    }
+}
+
+fn compare_inputs(
+    scope: ChartExtSchemaScope,
+    old: Option<&UiSchema>,
+    new: Option<&UiSchema>,
+    changes: &mut Vec<ChartExtChange>,
+) {
+    let empty = UiSchema::V0(UiSchemaV0::default());
+    let old = old.unwrap_or(&empty);
+    let new = new.unwrap_or(&empty);
+    let migration = new.migration_from(old);
+
+    for input in new.get_inputs().iter().filter(|input| {
+        !old.get_inputs().iter().any(|old| old.id == input.id)
+            && !migration.renamed.iter().any(|rename| rename.to == input.id)
+    }) {
+        changes.push(ChartExtChange::InputAdded {
+            scope: scope.clone(),
+            input: input.id.clone(),
+        });
+    }
+    for id in migration.removed {
+        changes.push(ChartExtChange::InputRemoved {
+            scope: scope.clone(),
+            input: id,
+        });
+    }
+    for rename in migration.renamed {
+        changes.push(ChartExtChange::InputRenamed {
+            scope: scope.clone(),
+            from: rename.from,
+            to: rename.to,
+        });
+    }
+    for retype in migration.retyped {
+        changes.push(ChartExtChange::InputRetyped {
+            scope: scope.clone(),
+            input: retype.id,
+        });
+    }
+    for id in migration.newly_required {
+        changes.push(ChartExtChange::InputNowRequired {
+            scope: scope.clone(),
+            input: id,
+        });
+    }
+}
+
+fn compare_action(
+    old: &ChartExtActionV0,
+    new: &ChartExtActionV0,
+    changes: &mut Vec<ChartExtChange>,
+) {
+    if target_changed(&old.target, &new.target) {
+        changes.push(ChartExtChange::ActionTargetChanged(old.id.clone()));
+    }
+    if old.allowed_role != new.allowed_role {
+        changes.push(ChartExtChange::ActionRoleChanged {
+            action: old.id.clone(),
+            from: old.allowed_role,
+            to: new.allowed_role,
+        });
+    }
+    let mut old_statuses = old.allowed_on_statuses.clone();
+    let mut new_statuses = new.allowed_on_statuses.clone();
+    old_statuses.sort();
+    new_statuses.sort();
+    if old_statuses != new_statuses {
+        changes.push(ChartExtChange::ActionStatusesChanged {
+            action: old.id.clone(),
+            from: old_statuses,
+            to: new_statuses,
+        });
+    }
+    if old.long_running.is_some() != new.long_running.is_some() {
+        changes.push(ChartExtChange::ActionLongRunningChanged {
+            action: old.id.clone(),
+            long_running: new.long_running.is_some(),
+        });
+    }
+    if old.response.clone().unwrap_or_default() != new.response.clone().unwrap_or_default() {
+        changes.push(ChartExtChange::ActionResponseChanged(old.id.clone()));
+    }
+    compare_inputs(
+        ChartExtSchemaScope::Action(old.id.clone()),
+        old.ui_schema.as_ref(),
+        new.ui_schema.as_ref(),
+        changes,
+    );
+}
+
+/// Whether calling the target may now send a different request
+fn target_changed(old: &ChartExtActionTarget, new: &ChartExtActionTarget) -> bool {
+    old.endpoint != new.endpoint
+        || old.path != new.path
+        || old.method != new.method
+        || old.body_encoding() != new.body_encoding()
+}
+
+fn resource_type_specs(chart_ext: &ChartExt) -> Vec<(String, &ChartExtResourceTypeV1Beta1Spec)> {
+    chart_ext
+        .resource_types
+        .iter()
+        .flat_map(|resource_types| resource_types.0.iter())
+        .map(|resource_type| match resource_type {
+            ChartExtResourceType::V1Beta1(rt) => (rt.key.clone(), &rt.spec),
+        })
+        .collect()
+}
+
+fn compare_resource_type(
+    key: &str,
+    old: &ChartExtResourceTypeV1Beta1Spec,
+    new: &ChartExtResourceTypeV1Beta1Spec,
+    changes: &mut Vec<ChartExtChange>,
+) {
+    if old.global != new.global {
+        changes.push(ChartExtChange::ResourceTypeGlobalChanged {
+            resource_type: key.to_owned(),
+            global: new.global,
+        });
+    }
+    for (lifecycle_action, old_action, new_action) in [
+        ("create", &old.lifecycle.create, &new.lifecycle.create),
+        ("update", &old.lifecycle.update, &new.lifecycle.update),
+        ("delete", &old.lifecycle.delete, &new.lifecycle.delete),
+    ] {
+        compare_lifecycle_action(
+            key,
+            lifecycle_action,
+            old_action.as_ref(),
+            new_action.as_ref(),
+            changes,
+        );
+    }
+    compare_inputs(
+        ChartExtSchemaScope::ResourceType(key.to_owned()),
+        Some(&UiSchema::V0(old.values_ui.clone())),
+        Some(&UiSchema::V0(new.values_ui.clone())),
+        changes,
+    );
+}
+
+/// A missing lifecycle action has no target, and is allowed to the
+/// default role
+fn compare_lifecycle_action(
+    key: &str,
+    lifecycle_action: &str,
+    old: Option<&ChartExtResourceLifecycleActionV1Beta1>,
+    new: Option<&ChartExtResourceLifecycleActionV1Beta1>,
+    changes: &mut Vec<ChartExtChange>,
+) {
+    let default = ChartExtResourceLifecycleActionV1Beta1::default();
+    let old = old.unwrap_or(&default);
+    let new = new.unwrap_or(&default);
+    let target_changed = match (old.target.as_ref(), new.target.as_ref()) {
+        (Some(old_target), Some(new_target)) => target_changed(old_target, new_target),
+        (old_target, new_target) => old_target.is_some() != new_target.is_some(),
+    };
+    if target_changed {
+        changes.push(ChartExtChange::LifecycleTargetChanged {
+            resource_type: key.to_owned(),
+            lifecycle_action: lifecycle_action.to_owned(),
+        });
+    }
+    let old_role = old.allowed_role();
+    let new_role = new.allowed_role();
+    if old_role != new_role {
+        changes.push(ChartExtChange::LifecycleRoleChanged {
+            resource_type: key.to_owned(),
+            lifecycle_action: lifecycle_action.to_owned(),
+            from: old_role,
+            to: new_role,
+        });
+    }
+}
+
+fn compare_features(
+    old: &ChartExtFeatures,
+    new: &ChartExtFeatures,
+    changes: &mut Vec<ChartExtChange>,
+) {
+    if old.cardinality() != new.cardinality() {
+        changes.push(ChartExtChange::CardinalityChanged {
+            from: old.cardinality().clone(),
+            to: new.cardinality().clone(),
+        });
+    }
+
+    let old_ingress = old.ingress();
+    let new_ingress = new.ingress();
+    match (old_ingress.enabled, new_ingress.enabled) {
+        (false, true) => changes.push(ChartExtChange::IngressEnabled),
+        (true, false) => changes.push(ChartExtChange::IngressDisabled),
+        (true, true) if old_ingress.hostname_format != new_ingress.hostname_format => {
+            changes.push(ChartExtChange::IngressHostnameFormatChanged {
+                from: old_ingress.hostname_format,
+                to: new_ingress.hostname_format,
+            })
+        }
+        _ => (),
+    }
+
+    match (old.status().is_some(), new.status().is_some()) {
+        (false, true) => changes.push(ChartExtChange::StatusEnabled),
+        (true, false) => changes.push(ChartExtChange::StatusDisabled),
+        _ => (),
+    }
+}
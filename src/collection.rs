@@ -1,5 +1,5 @@
-use crate::UiSchemaInputError;
-use serde::{de::DeserializeOwned, Serialize};
+use crate::{UiSchemaInputError, UiSchemaInputFieldValue};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use uuid::Uuid;
 
 pub trait UiSchemaCollections
@@ -15,4 +15,224 @@ where
         id: &str,
         property: &str,
     ) -> Result<serde_json::Value, UiSchemaInputError<Self::Error>>;
+
+    /// Declare the properties items of this collection expose, used for
+    /// validating `FieldProperty` outputs when a chart is loaded. Only
+    /// property names are validated, outputs don't declare a type to
+    /// check `property_type` against. Returns `None` when properties are
+    /// not declared, skipping validation.
+    fn properties(&self) -> Option<Vec<UiSchemaCollectionProperty>> {
+        None
+    }
+
+    /// List the IDs of all collection items matching every one of the
+    /// given filters. Filters are already resolved, so each value is the
+    /// actual value the item's field is compared against.
+    #[allow(async_fn_in_trait)]
+    async fn list(
+        &self,
+        _env_id: Uuid,
+        _filters: &[UiSchemaInputFieldValue],
+    ) -> Result<Vec<String>, UiSchemaInputError<Self::Error>> {
+        Err(UiSchemaInputError::UnsupportedCollection(self.to_string()))
+    }
+
+    /// Return those of `ids` matching all filters. The default
+    /// implementation lists the collection once, implementations may
+    /// override this with a more efficient lookup.
+    #[allow(async_fn_in_trait)]
+    async fn filter_items(
+        &self,
+        env_id: Uuid,
+        ids: &[&str],
+        filters: &[UiSchemaInputFieldValue],
+    ) -> Result<Vec<String>, UiSchemaInputError<Self::Error>> {
+        let listed = self.list(env_id, filters).await?;
+        Ok(ids
+            .iter()
+            .filter(|id| listed.iter().any(|item_id| item_id == *id))
+            .map(|id| id.to_string())
+            .collect())
+    }
+
+    /// Return a page of collection items matching the filters and the
+    /// text query, for populating `CollectionSelect` options.
+    ///
+    /// The default implementation goes through `list`, searches in item
+    /// IDs and uses them as item names. Implementations should override
+    /// this to search by name and page in their storage.
+    #[allow(async_fn_in_trait)]
+    async fn search(
+        &self,
+        env_id: Uuid,
+        filters: &[UiSchemaInputFieldValue],
+        query: &UiSchemaCollectionQuery,
+    ) -> Result<UiSchemaCollectionPage, UiSchemaInputError<Self::Error>> {
+        let ids: Vec<String> = self
+            .list(env_id, filters)
+            .await?
+            .into_iter()
+            .filter(|id| query.matches(id))
+            .collect();
+        let num_total = ids.len() as u64;
+        let items = ids
+            .into_iter()
+            .skip(query.offset())
+            .take(query.per_page() as usize)
+            .map(|id| UiSchemaCollectionItem {
+                name: id.clone(),
+                id,
+            })
+            .collect();
+        Ok(UiSchemaCollectionPage {
+            items,
+            page: query.page,
+            per_page: query.per_page(),
+            num_total,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct UiSchemaCollectionProperty {
+    pub name: String,
+    /// Informational, e.g. for schema editors, see
+    /// `UiSchemaCollections::properties`
+    pub property_type: UiSchemaCollectionPropertyType,
+}
+
+impl UiSchemaCollectionProperty {
+    pub fn new(name: &str, property_type: UiSchemaCollectionPropertyType) -> Self {
+        Self {
+            name: name.to_owned(),
+            property_type,
+        }
+    }
+}
+
+/// The JSON type of a collection property's or an action response
+/// field's value
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum UiSchemaCollectionPropertyType {
+    String,
+    Number,
+    Boolean,
+    Object,
+    Array,
+}
+
+impl UiSchemaCollectionPropertyType {
+    /// Whether `value` is of this type
+    pub fn matches(&self, value: &serde_json::Value) -> bool {
+        matches!(
+            (self, value),
+            (Self::String, serde_json::Value::String(_))
+                | (Self::Number, serde_json::Value::Number(_))
+                | (Self::Boolean, serde_json::Value::Bool(_))
+                | (Self::Object, serde_json::Value::Object(_))
+                | (Self::Array, serde_json::Value::Array(_))
+        )
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema, utoipa::IntoParams))]
+pub struct UiSchemaCollectionQuery {
+    /// Free text to search for, case insensitive
+    #[serde(default)]
+    pub search: Option<String>,
+    /// Page number, starting from 1
+    #[serde(default = "default_page", deserialize_with = "deserialize_page")]
+    pub page: u64,
+    /// Items per page, between 1 and `UiSchemaCollectionQuery::MAX_PER_PAGE`.
+    /// Larger values are clamped to the maximum.
+    #[serde(
+        default = "default_per_page",
+        deserialize_with = "deserialize_per_page"
+    )]
+    pub per_page: u64,
+}
+
+impl Default for UiSchemaCollectionQuery {
+    fn default() -> Self {
+        Self {
+            search: None,
+            page: default_page(),
+            per_page: default_per_page(),
+        }
+    }
+}
+
+impl UiSchemaCollectionQuery {
+    pub const MAX_PER_PAGE: u64 = 1000;
+
+    /// Items per page, clamped to `MAX_PER_PAGE`
+    pub fn per_page(&self) -> u64 {
+        self.per_page.clamp(1, Self::MAX_PER_PAGE)
+    }
+
+    /// Number of items before the requested page. Saturates instead of
+    /// overflowing for huge page numbers, which then return no items.
+    pub fn offset(&self) -> usize {
+        usize::try_from(self.page.saturating_sub(1).saturating_mul(self.per_page()))
+            .unwrap_or(usize::MAX)
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        self.search
+            .as_deref()
+            .is_none_or(|search| text.to_lowercase().contains(&search.to_lowercase()))
+    }
+}
+
+fn default_page() -> u64 {
+    1
+}
+
+fn default_per_page() -> u64 {
+    50
+}
+
+fn deserialize_page<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match u64::deserialize(deserializer)? {
+        0 => Err(serde::de::Error::invalid_value(
+            serde::de::Unexpected::Unsigned(0),
+            &"a page number starting from 1",
+        )),
+        page => Ok(page),
+    }
+}
+
+fn deserialize_per_page<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match u64::deserialize(deserializer)? {
+        0 => Err(serde::de::Error::invalid_value(
+            serde::de::Unexpected::Unsigned(0),
+            &"a positive number of items per page",
+        )),
+        per_page => Ok(per_page),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct UiSchemaCollectionItem {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct UiSchemaCollectionPage {
+    pub items: Vec<UiSchemaCollectionItem>,
+    pub page: u64,
+    pub per_page: u64,
+    pub num_total: u64,
 }
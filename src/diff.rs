@@ -0,0 +1,188 @@
+use crate::ui_schema::{RenderedSecret, UiSchema, UiSchemaOutputValue};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+type Map = serde_json::Map<String, serde_json::Value>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum UiSchemaChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A single difference between two sets of rendered values. Array
+/// items are referenced in the path as `[INDEX]`, the same syntax used
+/// in output paths.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct UiSchemaValuesChange {
+    pub path: Vec<String>,
+    pub kind: UiSchemaChangeKind,
+    /// Omitted for values of sensitive inputs, see `UiSchema::diff_values`
+    pub old_value: Option<serde_json::Value>,
+    pub new_value: Option<serde_json::Value>,
+}
+
+/// A difference in a single secret key. Secret values are never
+/// included, only the fact they changed.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct UiSchemaSecretChange {
+    pub name: String,
+    pub key: String,
+    pub kind: UiSchemaChangeKind,
+}
+
+/// Compare the values returned by `UiSchema::get_values` before and
+/// after a change. Added or removed objects are reported once at the
+/// top-most path instead of once per leaf. Values are included as is,
+/// use `UiSchema::diff_values` to omit values of sensitive inputs.
+pub fn diff_values(old: &Map, new: &Map) -> Vec<UiSchemaValuesChange> {
+    let mut changes = Vec::new();
+    diff_maps(&mut Vec::new(), old, new, &mut changes);
+    changes
+}
+
+fn diff_maps(
+    path: &mut Vec<String>,
+    old: &Map,
+    new: &Map,
+    changes: &mut Vec<UiSchemaValuesChange>,
+) {
+    for (key, old_value) in old.iter() {
+        path.push(key.to_owned());
+        diff_nodes(path, Some(old_value), new.get(key), changes);
+        path.pop();
+    }
+    for (key, new_value) in new.iter().filter(|(key, _)| !old.contains_key(*key)) {
+        path.push(key.to_owned());
+        diff_nodes(path, None, Some(new_value), changes);
+        path.pop();
+    }
+}
+
+fn diff_nodes(
+    path: &mut Vec<String>,
+    old: Option<&serde_json::Value>,
+    new: Option<&serde_json::Value>,
+    changes: &mut Vec<UiSchemaValuesChange>,
+) {
+    let kind = match (old, new) {
+        (Some(old), Some(new)) if old == new => return,
+        (Some(serde_json::Value::Object(old)), Some(serde_json::Value::Object(new))) => {
+            return diff_maps(path, old, new, changes);
+        }
+        (Some(serde_json::Value::Array(old)), Some(serde_json::Value::Array(new))) => {
+            for index in 0..old.len().max(new.len()) {
+                path.push(format!("[{index}]"));
+                diff_nodes(path, old.get(index), new.get(index), changes);
+                path.pop();
+            }
+            return;
+        }
+        (Some(_), Some(_)) => UiSchemaChangeKind::Changed,
+        (Some(_), None) => UiSchemaChangeKind::Removed,
+        (None, Some(_)) => UiSchemaChangeKind::Added,
+        (None, None) => return,
+    };
+    changes.push(UiSchemaValuesChange {
+        path: path.clone(),
+        kind,
+        old_value: old.cloned(),
+        new_value: new.cloned(),
+    });
+}
+
+/// Compare the secrets returned by `UiSchema::get_secrets` before and
+/// after a change.
+pub fn diff_secrets(old: &[RenderedSecret], new: &[RenderedSecret]) -> Vec<UiSchemaSecretChange> {
+    let collect = |secrets: &[RenderedSecret]| -> BTreeMap<(String, String), String> {
+        secrets
+            .iter()
+            .flat_map(|secret| {
+                secret
+                    .attrs
+                    .iter()
+                    .map(|(key, value)| ((secret.name.clone(), key.clone()), value.clone()))
+            })
+            .collect()
+    };
+    let old = collect(old);
+    let new = collect(new);
+
+    let mut changes: Vec<UiSchemaSecretChange> = old
+        .iter()
+        .filter_map(|(name_key, old_value)| match new.get(name_key) {
+            None => Some((name_key, UiSchemaChangeKind::Removed)),
+            Some(new_value) if new_value != old_value => {
+                Some((name_key, UiSchemaChangeKind::Changed))
+            }
+            Some(_) => None,
+        })
+        .chain(
+            new.keys()
+                .filter(|name_key| !old.contains_key(*name_key))
+                .map(|name_key| (name_key, UiSchemaChangeKind::Added)),
+        )
+        .map(|((name, key), kind)| UiSchemaSecretChange {
+            name: name.clone(),
+            key: key.clone(),
+            kind,
+        })
+        .collect();
+    changes.sort_by(|a, b| (&a.name, &a.key).cmp(&(&b.name, &b.key)));
+    changes
+}
+
+impl UiSchema {
+    /// Same as `diff_values`, but omits the old and new value of changes
+    /// that may include the value of a sensitive input, like
+    /// `diff_secrets` does for secrets.
+    pub fn diff_values(&self, old: &Map, new: &Map) -> Vec<UiSchemaValuesChange> {
+        let mut changes = diff_values(old, new);
+        for change in changes.iter_mut() {
+            if self.is_sensitive_path(&change.path) {
+                change.old_value = None;
+                change.new_value = None;
+            }
+        }
+        changes
+    }
+
+    fn is_sensitive_path(&self, path: &[String]) -> bool {
+        self.get_outputs_for_path(path).iter().any(|output| {
+            self.get_inputs()
+                .iter()
+                .any(|input| input.id == output.value.input() && input.sensitive)
+        })
+    }
+
+    /// Find the outputs that may have produced a change at `path`, as
+    /// returned from `diff_values`. Since changes are reported at the
+    /// top-most changed path, this includes all outputs under it.
+    pub fn get_outputs_for_path(&self, path: &[String]) -> Vec<&UiSchemaOutputValue> {
+        self.get_outputs()
+            .values
+            .iter()
+            .filter(|output| {
+                output
+                    .path
+                    .iter()
+                    .zip(path.iter())
+                    .all(|(output_part, part)| path_parts_match(output_part, part))
+            })
+            .collect()
+    }
+}
+
+/// Array references in output paths (`[+]`, `[=]`) can't be resolved
+/// without rendering, so they match any array index.
+fn path_parts_match(output_part: &str, part: &str) -> bool {
+    let is_array_ref = |p: &str| p.starts_with('[') && p.ends_with(']');
+    match (output_part, part) {
+        ("[+]" | "[=]", part) => is_array_ref(part),
+        (output_part, part) => output_part == part,
+    }
+}
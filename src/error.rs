@@ -33,9 +33,168 @@ where
     #[error("Collection not supported for references: {0}")]
     UnsupportedCollection(String),
 
+    #[error("The filters of the {0} input refer to the {1} input, which has no value")]
+    FilterInputMissing(String, String),
+
+    #[error("The {0} input field selects {1}, which doesn't match the input filters")]
+    CollectionItemFilteredOut(String, String),
+
     #[error("Unknown property {0} of collection {1}")]
     UnknownProperty(String, String),
 
     #[error("Error while resolving collection property: {0}")]
     CollectionError(#[from] CollectionError),
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum UiSchemaValidationError {
+    #[error("An output refers to the {0} input, but it doesn't appear in the schema")]
+    MissingInputSchema(String),
+
+    #[error("An output refers to the input field {0}, but that input is not a collection")]
+    InputNotACollection(String),
+
+    #[error("Could not find a collection named {0}: {1}")]
+    InvalidCollectionName(serde_json::Value, serde_json::Error),
+
+    #[error("Unknown property {0} of collection {1}")]
+    UnknownProperty(String, String),
+
+    #[error(
+        "The filters of the {0} input refer to the {1} input, but it doesn't appear in the schema"
+    )]
+    UnknownFilterInput(String, String),
+
+    #[error("Layout section {0} refers to the {1} input, but it doesn't appear in the schema")]
+    UnknownSectionInput(String, String),
+
+    #[error("The {0} input appears in both the {1} and {2} layout sections")]
+    DuplicateSectionInput(String, String, String),
+
+    #[error(transparent)]
+    Visibility(#[from] UiSchemaVisibilityIssue),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChartExtValidationError {
+    #[error("Error in values UI: {0}")]
+    ValuesUi(UiSchemaValidationError),
+    #[error("Error in action {0}: {1}")]
+    Action(String, UiSchemaValidationError),
+    #[error("Error in resource type {0}: {1}")]
+    ResourceType(String, UiSchemaValidationError),
+    #[error("Action {0} is allowed on status {1}, which is not declared in the status feature")]
+    UnknownActionStatus(String, String),
+    #[error("Error in path of action {0}: {1}")]
+    ActionPath(String, ChartExtActionTemplateError),
+    #[error("Error in dry run of action {0}: {1}")]
+    DryRun(String, ChartExtActionResponseError),
+}
+
+/// A problem with the `showIf`/`showIfAll` conditions of a schema,
+/// found without evaluating them against actual inputs.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize, thiserror::Error)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum UiSchemaVisibilityIssue {
+    #[error("The condition of the {input} input refers to {variable}, which is not an input")]
+    UnknownVariable { input: String, variable: String },
+
+    #[error("The condition of the {section} section refers to {variable}, which is not an input")]
+    UnknownSectionVariable { section: String, variable: String },
+
+    #[error("The conditions of inputs {0:?} depend on each other")]
+    DependencyCycle(Vec<String>),
+
+    #[error("The {0} input can never be visible")]
+    NeverVisible(String),
+
+    #[error(
+        "The condition of the {input} input refers to {dependency}, which can never be visible"
+    )]
+    DependsOnNeverVisible { input: String, dependency: String },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UiSchemaVisibilityError {
+    #[error("Error evaluating showIf of the {0} input: {1}")]
+    ShowIfError(String, String),
+
+    #[error("showIf of the {0} input evaluated to {1} instead of a boolean")]
+    ShowIfNotBool(String, serde_json::Value),
+
+    #[error("Error evaluating showIf of the {0} section: {1}")]
+    SectionShowIfError(String, String),
+
+    #[error("showIf of the {0} section evaluated to {1} instead of a boolean")]
+    SectionShowIfNotBool(String, serde_json::Value),
+
+    #[error("The showIf conditions of the inputs don't settle on a visibility")]
+    NotConverging,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChartExtStatusError<ResolveError> {
+    #[error("Error resolving the status endpoint: {0}")]
+    Resolve(ResolveError),
+
+    #[error("Error requesting status: {0}")]
+    Request(#[from] reqwest::Error),
+
+    /// The body is truncated to its first `STATUS_BODY_SNIPPET_LEN` bytes
+    #[error("Status endpoint returned an invalid status: {0}, body starts with: {1}")]
+    Parse(serde_json::Error, String),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChartExtActionResponseError {
+    #[error("Action failed with status {0}: {1}")]
+    Status(reqwest::StatusCode, String),
+
+    #[error("Action returned invalid JSON: {0}")]
+    InvalidJson(serde_json::Error),
+
+    #[error("Action returned {0} instead of an object")]
+    NotAnObject(serde_json::Value),
+
+    #[error("Action response is missing the {0} field")]
+    MissingField(String),
+
+    #[error("Field {0} of the action response is not of type {1:?}")]
+    InvalidFieldType(String, crate::UiSchemaCollectionPropertyType),
+
+    #[error("Action returned an invalid notice: {0}")]
+    InvalidNotice(serde_json::Error),
+
+    #[error("Action is not long-running")]
+    NotLongRunning,
+
+    #[error("Action returned an invalid job or job status: {0}")]
+    InvalidJob(serde_json::Error),
+
+    #[error("Timed out waiting for job {0}")]
+    JobTimeout(String),
+
+    #[error("Action has no dry run")]
+    NoDryRun,
+
+    #[error("Invalid dry run header {0}")]
+    InvalidDryRunHeader(String),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChartExtActionTemplateError {
+    #[error("Unclosed variable in template {0}")]
+    Unclosed(String),
+
+    #[error("Unknown template variable {0}")]
+    UnknownVariable(String),
+
+    #[error("No value for template variable {0}")]
+    MissingValue(String),
+
+    #[error("Rendered URL {0} is invalid: {1}")]
+    InvalidUrl(String, url::ParseError),
+
+    #[error("Template variable {0} can't be set to {1}, only strings, numbers and booleans are supported, and path segments can't be . or ..")]
+    InvalidValue(String, serde_json::Value),
+}
@@ -1,8 +1,10 @@
 use super::actions::ChartExtActions;
 use super::features::ChartExtFeatures;
 use super::ui_schema::UiSchema;
+use crate::collection::UiSchemaCollections;
+use crate::error::ChartExtValidationError;
 use crate::metadata::ChartMetadata;
-use crate::resource_types::ChartExtResourceTypes;
+use crate::resource_types::{ChartExtResourceType, ChartExtResourceTypes};
 use serde::{de::DeserializeOwned, Serialize};
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
@@ -43,6 +45,94 @@ impl ChartExt {
         }
     }
 
+    /// Same as `from_path`, additionally validating all UI schemas in the
+    /// chart against the collections `C`. Validation errors are reported
+    /// the same way as parsing errors.
+    pub async fn from_path_validated<C>(path: &Path) -> Result<Self, std::io::Error>
+    where
+        C: UiSchemaCollections,
+    {
+        let chart_ext = Self::from_path(path).await?;
+        Ok(match chart_ext.validate::<C>() {
+            Ok(()) => chart_ext,
+            Err(err) => Self::new_with_error(err.to_string()),
+        })
+    }
+
+    /// Validate the values UI schema, action schemas and resource type
+    /// schemas against the collections `C`.
+    pub fn validate<C>(&self) -> Result<(), ChartExtValidationError>
+    where
+        C: UiSchemaCollections,
+    {
+        if let Some(ui_schema) = self.ui_schema.as_ref() {
+            ui_schema
+                .validate::<C>()
+                .map_err(ChartExtValidationError::ValuesUi)?;
+        }
+        for action in self
+            .actions
+            .iter()
+            .flat_map(|actions| actions.get_actions())
+        {
+            if let Some(ui_schema) = action.ui_schema.as_ref() {
+                ui_schema
+                    .validate::<C>()
+                    .map_err(|err| ChartExtValidationError::Action(action.id.clone(), err))?;
+            }
+            action
+                .validate_path()
+                .map_err(|err| ChartExtValidationError::ActionPath(action.id.clone(), err))?;
+            if let Some(dry_run) = action.dry_run.as_ref() {
+                dry_run
+                    .header()
+                    .map_err(|err| ChartExtValidationError::DryRun(action.id.clone(), err))?;
+            }
+            if let Some(status) = self.features.as_ref().and_then(|f| f.status()) {
+                if let Some(unknown) = action
+                    .allowed_on_statuses
+                    .iter()
+                    .find(|name| !status.statuses.is_empty() && !status.statuses.contains(name))
+                {
+                    return Err(ChartExtValidationError::UnknownActionStatus(
+                        action.id.clone(),
+                        unknown.clone(),
+                    ));
+                }
+            }
+        }
+        for resource_type in self.resource_types.iter().flat_map(|rts| rts.0.iter()) {
+            match resource_type {
+                ChartExtResourceType::V1Beta1(rt) => rt
+                    .spec
+                    .values_ui
+                    .validate::<C>()
+                    .map_err(|err| ChartExtValidationError::ResourceType(rt.key.clone(), err))?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace all user facing texts in the chart with their text in
+    /// `locale`, see `LocalizedString::get` for fallback rules.
+    pub fn localize(&mut self, locale: &str) {
+        if let Some(ui_schema) = self.ui_schema.as_mut() {
+            ui_schema.localize(locale);
+        }
+        if let Some(actions) = self.actions.as_mut() {
+            actions.localize(locale);
+        }
+        for resource_type in self
+            .resource_types
+            .iter_mut()
+            .flat_map(|rts| rts.0.iter_mut())
+        {
+            match resource_type {
+                ChartExtResourceType::V1Beta1(rt) => rt.spec.localize(locale),
+            }
+        }
+    }
+
     pub fn new_with_error(error: String) -> Self {
         Self {
             metadata: None,
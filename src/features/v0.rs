@@ -10,7 +10,7 @@ pub struct ChartExtFeaturesV0 {
     #[serde(default)]
     pub standard_ingress: bool,
 
-    /// Sets an HTTP endpoint that returns a platz_sdk::PlatzStatus
+    /// Sets an HTTP endpoint that returns a `PlatzStatus`
     /// and displayed as part of the deployment page.
     pub status: Option<ChartExtStatusFeature>,
 
@@ -40,9 +40,13 @@ pub struct ChartExtStatusFeature {
     pub endpoint: ChartExtActionEndpoint,
     pub path: String,
     pub refresh_interval_secs: u64,
+    /// Status names the endpoint may return. When set, the
+    /// `allowed_on_statuses` of actions are validated against them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub statuses: Vec<String>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub enum ChartExtCardinality {
     #[default]
@@ -20,7 +20,7 @@ pub struct ChartExtFeaturesSpec {
     #[serde(default)]
     pub ingress: ChartExtIngress,
 
-    /// Sets an HTTP endpoint that returns a platz_sdk::PlatzStatus
+    /// Sets an HTTP endpoint that returns a `PlatzStatus`
     /// and displayed as part of the deployment page.
     pub status: Option<ChartExtStatusFeature>,
 
@@ -69,7 +69,7 @@ impl From<bool> for ChartExtIngress {
     }
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub enum ChartExtIngressHostnameFormat {
     Name,
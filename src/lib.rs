@@ -1,17 +1,29 @@
 pub mod actions;
+mod changes;
 mod collection;
+mod diff;
 mod error;
 pub mod ext_type;
 pub mod features;
+mod localization;
 mod metadata;
+mod migration;
+mod normalize;
 pub mod resource_types;
+mod status;
 pub mod ui_schema;
 mod versions;
+mod visibility;
 
 pub use actions::*;
+pub use changes::*;
 pub use collection::*;
+pub use diff::*;
 pub use error::*;
 pub use ext_type::*;
 pub use features::*;
+pub use localization::*;
 pub use metadata::*;
+pub use migration::*;
+pub use status::*;
 pub use ui_schema::*;
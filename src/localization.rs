@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Locale used when a text is not available in the requested locale
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// User facing text, either a plain string or a map from locale to the
+/// text in that locale:
+///
+/// ```yaml
+/// label: Name
+/// ```
+///
+/// ```yaml
+/// label:
+///   en: Name
+///   de: Name
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(untagged)]
+pub enum LocalizedString {
+    Plain(String),
+    Localized(BTreeMap<String, String>),
+}
+
+impl LocalizedString {
+    /// Get the text for `locale` (e.g. `de-AT`), falling back to its
+    /// language (`de`), then to `DEFAULT_LOCALE`, then to any locale.
+    pub fn get(&self, locale: &str) -> &str {
+        match self {
+            Self::Plain(text) => text,
+            Self::Localized(texts) => {
+                let language = locale.split(['-', '_']).next().unwrap_or(locale);
+                [locale, language, DEFAULT_LOCALE]
+                    .iter()
+                    .find_map(|locale| texts.get(*locale))
+                    .or_else(|| texts.values().next())
+                    .map_or("", String::as_str)
+            }
+        }
+    }
+
+    /// Replace this text with its plain text in `locale`
+    pub fn localize(&mut self, locale: &str) {
+        *self = Self::Plain(self.get(locale).to_owned());
+    }
+}
+
+impl From<&str> for LocalizedString {
+    fn from(text: &str) -> Self {
+        Self::Plain(text.to_owned())
+    }
+}
+
+impl From<String> for LocalizedString {
+    fn from(text: String) -> Self {
+        Self::Plain(text)
+    }
+}
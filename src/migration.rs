@@ -0,0 +1,121 @@
+use crate::ui_schema::{UiSchema, UiSchemaInput, UiSchemaInputType};
+use serde::{Deserialize, Serialize};
+
+/// Differences between the inputs of two versions of a `UiSchema`,
+/// as they affect inputs stored for existing deployments.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct UiSchemaMigration {
+    /// Inputs removed from the new schema. Stored values for these
+    /// inputs are no longer used.
+    pub removed: Vec<String>,
+    /// Inputs declaring a previous ID using `renamedFrom`
+    pub renamed: Vec<UiSchemaInputRename>,
+    /// Inputs whose type changed, stored values may no longer be valid
+    pub retyped: Vec<UiSchemaInputRetype>,
+    /// Inputs which are now required and have no initial value, so
+    /// existing deployments have to provide a value before upgrading.
+    pub newly_required: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct UiSchemaInputRename {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct UiSchemaInputRetype {
+    pub id: String,
+    #[cfg_attr(feature = "utoipa", schema(value_type = crate::SerializedUiSchemaInputType))]
+    pub old_type: UiSchemaInputType,
+    #[cfg_attr(feature = "utoipa", schema(value_type = crate::SerializedUiSchemaInputType))]
+    pub new_type: UiSchemaInputType,
+}
+
+impl UiSchemaMigration {
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.retyped.is_empty()
+            && self.newly_required.is_empty()
+    }
+
+    /// Upgrade stored inputs by moving values of renamed inputs to their
+    /// new IDs. Values already stored under the new ID are kept.
+    pub fn apply(&self, inputs: &serde_json::Value) -> serde_json::Value {
+        let mut inputs = inputs.clone();
+        if let Some(inputs_map) = inputs.as_object_mut() {
+            for rename in self.renamed.iter() {
+                if let Some(value) = inputs_map.remove(&rename.from) {
+                    inputs_map.entry(rename.to.clone()).or_insert(value);
+                }
+            }
+        }
+        inputs
+    }
+}
+
+impl UiSchema {
+    /// Compare the inputs of this schema with an older version of it
+    pub fn migration_from(&self, old: &UiSchema) -> UiSchemaMigration {
+        let old_inputs = old.get_inputs();
+        let new_inputs = self.get_inputs();
+        let find_old = |id: &str| old_inputs.iter().find(|input| input.id == id);
+        let in_new = |id: &str| new_inputs.iter().any(|input| input.id == id);
+
+        let mut migration = UiSchemaMigration::default();
+        for new_input in new_inputs.iter() {
+            let old_input = match find_old(&new_input.id) {
+                Some(old_input) => Some(old_input),
+                None => new_input
+                    .renamed_from
+                    .iter()
+                    .filter(|from| !in_new(from))
+                    .find_map(|from| find_old(from))
+                    .inspect(|old_input| {
+                        migration.renamed.push(UiSchemaInputRename {
+                            from: old_input.id.clone(),
+                            to: new_input.id.clone(),
+                        })
+                    }),
+            };
+
+            if let Some(old_input) = old_input {
+                if old_input.input_type != new_input.input_type {
+                    migration.retyped.push(UiSchemaInputRetype {
+                        id: new_input.id.clone(),
+                        old_type: old_input.input_type.clone(),
+                        new_type: new_input.input_type.clone(),
+                    });
+                }
+            }
+
+            if is_required_without_default(new_input)
+                && !old_input.is_some_and(is_required_without_default)
+            {
+                migration.newly_required.push(new_input.id.clone());
+            }
+        }
+
+        migration.removed = old_inputs
+            .iter()
+            .filter(|old_input| {
+                !in_new(&old_input.id)
+                    && !migration
+                        .renamed
+                        .iter()
+                        .any(|rename| rename.from == old_input.id)
+            })
+            .map(|old_input| old_input.id.clone())
+            .collect();
+
+        migration
+    }
+}
+
+fn is_required_without_default(input: &UiSchemaInput) -> bool {
+    input.required && input.initial_value().is_none()
+}
@@ -0,0 +1,60 @@
+use crate::error::UiSchemaVisibilityError;
+use crate::ui_schema::{UiSchema, UiSchemaInput, UiSchemaInputSingleType};
+
+impl UiSchema {
+    /// Get the canonical form of `inputs` to store for a deployment:
+    /// missing inputs get their initial value, numeric strings of number
+    /// inputs become numbers, and only fields of visible inputs are kept.
+    /// Inputs of a hidden layout section are not visible, so they're
+    /// dropped too, see `visible_inputs`.
+    pub fn normalize_inputs(
+        &self,
+        inputs: &serde_json::Value,
+    ) -> Result<serde_json::Value, UiSchemaVisibilityError> {
+        let mut normalized = serde_json::Map::new();
+        for schema_input in self.get_inputs().iter() {
+            let value = match inputs.get(&schema_input.id) {
+                Some(serde_json::Value::Null) | None => schema_input.initial_value().cloned(),
+                Some(value) => Some(coerce_value(schema_input, value)),
+            };
+            if let Some(value) = value {
+                normalized.insert(schema_input.id.clone(), value);
+            }
+        }
+
+        let normalized = serde_json::Value::Object(normalized);
+        let visible = self.visible_inputs(&normalized)?;
+        let mut canonical = serde_json::Map::new();
+        for schema_input in visible {
+            if let Some(value) = normalized.get(&schema_input.id) {
+                canonical.insert(schema_input.id.clone(), value.clone());
+            }
+        }
+        Ok(serde_json::Value::Object(canonical))
+    }
+}
+
+fn coerce_value(schema_input: &UiSchemaInput, value: &serde_json::Value) -> serde_json::Value {
+    if schema_input.input_type.single_type != UiSchemaInputSingleType::Number {
+        return value.clone();
+    }
+    match value {
+        serde_json::Value::Array(items) if schema_input.input_type.is_array => {
+            serde_json::Value::Array(items.iter().map(coerce_number).collect())
+        }
+        value => coerce_number(value),
+    }
+}
+
+/// Strings that aren't valid numbers are kept as is, so they're still
+/// reported by validation instead of silently dropped.
+fn coerce_number(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(s) => s
+            .trim()
+            .parse::<serde_json::Number>()
+            .map(serde_json::Value::Number)
+            .unwrap_or_else(|_| value.clone()),
+        value => value.clone(),
+    }
+}
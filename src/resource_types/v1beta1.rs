@@ -1,4 +1,5 @@
 use crate::actions::{ChartExtActionTarget, ChartExtActionUserDeploymentRole};
+use crate::localization::LocalizedString;
 use crate::ui_schema::UiSchemaV0;
 use crate::versions::{ChartExtKindResourceType, ChartExtVersionV1Beta1};
 use serde::{Deserialize, Serialize};
@@ -16,8 +17,8 @@ pub struct ChartExtResourceTypeV1Beta1 {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChartExtResourceTypeV1Beta1Spec {
-    pub name_singular: String,
-    pub name_plural: String,
+    pub name_singular: LocalizedString,
+    pub name_plural: LocalizedString,
     pub fontawesome_icon: String,
     #[serde(default)]
     pub global: bool,
@@ -26,6 +27,15 @@ pub struct ChartExtResourceTypeV1Beta1Spec {
     pub lifecycle: ChartExtResourceLifecycleV1Beta1,
 }
 
+impl ChartExtResourceTypeV1Beta1Spec {
+    /// Replace all user facing texts with their text in `locale`
+    pub fn localize(&mut self, locale: &str) {
+        self.name_singular.localize(locale);
+        self.name_plural.localize(locale);
+        self.values_ui.localize(locale);
+    }
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChartExtResourceLifecycleV1Beta1 {
@@ -37,6 +47,19 @@ pub struct ChartExtResourceLifecycleV1Beta1 {
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChartExtResourceLifecycleActionV1Beta1 {
+    /// Minimal role required, defaults to `Maintainer`
     pub allowed_role: Option<ChartExtActionUserDeploymentRole>,
     pub target: Option<ChartExtActionTarget>,
 }
+
+impl ChartExtResourceLifecycleActionV1Beta1 {
+    /// The minimal role required, see `allowed_role`
+    pub fn allowed_role(&self) -> ChartExtActionUserDeploymentRole {
+        self.allowed_role
+            .unwrap_or(ChartExtActionUserDeploymentRole::Maintainer)
+    }
+
+    pub fn is_allowed(&self, user_role: ChartExtActionUserDeploymentRole) -> bool {
+        self.allowed_role().allows(user_role)
+    }
+}
@@ -0,0 +1,118 @@
+use crate::actions::{
+    ChartExtActionClient, ChartExtActionMethod, ChartExtActionTarget, ChartExtActionTargetResolver,
+};
+use crate::error::ChartExtStatusError;
+use crate::features::ChartExtStatusFeature;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Status reported by a deployment's status endpoint, see
+/// `ChartExtStatusFeature`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct PlatzStatus {
+    pub status: PlatzStatusName,
+    pub primary_metric: Option<PlatzStatusMetric>,
+    #[serde(default)]
+    pub metrics: Vec<PlatzStatusMetric>,
+    #[serde(default)]
+    pub notices: Vec<PlatzStatusNotice>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct PlatzStatusName {
+    pub name: String,
+    pub color: PlatzStatusColor,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum PlatzStatusColor {
+    Primary,
+    Secondary,
+    Success,
+    Danger,
+    Warning,
+    Info,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct PlatzStatusMetric {
+    pub value: Decimal,
+    pub unit: String,
+    pub short_description: String,
+    pub color: Option<PlatzStatusColor>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum PlatzStatusNoticeLevel {
+    Info,
+    Warning,
+    Danger,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct PlatzStatusNotice {
+    pub level: PlatzStatusNoticeLevel,
+    pub text: String,
+}
+
+impl ChartExtStatusFeature {
+    /// How long to wait between calls to `fetch`
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_secs(self.refresh_interval_secs)
+    }
+
+    /// The status endpoint as a GET action target, for use with a
+    /// `ChartExtActionTargetResolver`.
+    pub fn target(&self) -> ChartExtActionTarget {
+        ChartExtActionTarget {
+            endpoint: self.endpoint.clone(),
+            path: self.path.clone(),
+            method: ChartExtActionMethod::Get,
+            timeout_secs: None,
+            retry: None,
+            body_encoding: None,
+        }
+    }
+
+    /// Fetch the current status of a deployment. Call this every
+    /// `refresh_interval` to poll for changes.
+    pub async fn fetch<R>(
+        &self,
+        client: &ChartExtActionClient,
+        resolver: &R,
+    ) -> Result<PlatzStatus, ChartExtStatusError<R::Error>>
+    where
+        R: ChartExtActionTargetResolver,
+    {
+        let url = resolver
+            .resolve(&self.target())
+            .await
+            .map_err(ChartExtStatusError::Resolve)?;
+        let response = client
+            .request(reqwest::Method::GET, url, None)
+            .send()
+            .await?
+            .error_for_status()?;
+        let body = response.text().await?;
+        serde_json::from_str(&body)
+            .map_err(|err| ChartExtStatusError::Parse(err, body_snippet(&body).to_owned()))
+    }
+}
+
+/// Maximum length of the body included in `ChartExtStatusError::Parse`
+pub const STATUS_BODY_SNIPPET_LEN: usize = 200;
+
+fn body_snippet(body: &str) -> &str {
+    let mut end = body.len().min(STATUS_BODY_SNIPPET_LEN);
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    &body[..end]
+}
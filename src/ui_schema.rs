@@ -1,11 +1,17 @@
 use crate::versions::ChartExtKindValuesUi;
 use crate::versions::ChartExtVersionV1Beta1;
+use crate::visibility::analyze_visibility;
+use crate::LocalizedString;
 use crate::UiSchemaCollections;
 use crate::UiSchemaInputError;
+use crate::UiSchemaValidationError;
+use crate::UiSchemaVisibilityError;
+use crate::{UiSchemaCollectionPage, UiSchemaCollectionQuery};
 use rust_decimal::Decimal;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use strum::{EnumDiscriminants, EnumString};
 use uuid::Uuid;
@@ -33,23 +39,347 @@ impl UiSchema {
         }
     }
 
+    pub fn get_layout(&self) -> Option<&UiSchemaLayout> {
+        match self {
+            Self::V1Beta1(v1) => v1.inner.layout.as_ref(),
+            Self::V0(v0) => v0.layout.as_ref(),
+        }
+    }
+
+    /// Replace all user facing texts with their text in `locale`
+    pub fn localize(&mut self, locale: &str) {
+        match self {
+            Self::V1Beta1(v1) => v1.inner.localize(locale),
+            Self::V0(v0) => v0.localize(locale),
+        }
+    }
+
     pub fn is_collection_in_inputs<C>(
         &self,
         inputs: &serde_json::Value,
         collection: &C,
         id: &str,
-    ) -> bool
+    ) -> Result<bool, serde_json::Error>
+    where
+        C: UiSchemaCollections,
+    {
+        Ok(!self
+            .find_collection_references(inputs, collection, id)?
+            .is_empty())
+    }
+
+    /// Find all inputs selecting the collection item `id`, including
+    /// inputs that are currently hidden, along with the output paths and
+    /// secret keys their values are rendered into.
+    pub fn find_collection_references<C>(
+        &self,
+        inputs: &serde_json::Value,
+        collection: &C,
+        id: &str,
+    ) -> Result<Vec<UiSchemaCollectionReference>, serde_json::Error>
     where
         C: UiSchemaCollections,
     {
-        let collection_value = serde_json::to_value(collection).unwrap();
-        self.get_inputs().iter().any(|input| {
-            let used_collection = match &input.input_type.single_type {
-                UiSchemaInputSingleType::CollectionSelect { collection } => Some(collection),
-                _ => None,
+        let collection_value = serde_json::to_value(collection)?;
+        let (visible, _) = self.rendered_visibility(inputs);
+        Ok(self
+            .get_inputs()
+            .iter()
+            .zip(visible)
+            .filter(|(input, _)| {
+                matches!(
+                    &input.input_type.single_type,
+                    UiSchemaInputSingleType::CollectionSelect { collection }
+                        if *collection == collection_value
+                ) && input.selects_item(inputs, id)
+            })
+            .map(|(input, visible)| {
+                let outputs = self.get_outputs();
+                let mut secrets = BTreeMap::new();
+                for (secret_name, attrs_schema) in outputs.secrets.0.iter() {
+                    let mut keys: Vec<String> = attrs_schema
+                        .iter()
+                        .filter(|(_, attr_schema)| attr_schema.input() == input.id)
+                        .map(|(key, _)| key.clone())
+                        .collect();
+                    if !keys.is_empty() {
+                        keys.sort();
+                        secrets.insert(secret_name.clone(), keys);
+                    }
+                }
+                UiSchemaCollectionReference {
+                    input: input.id.clone(),
+                    visible,
+                    output_paths: outputs
+                        .values
+                        .iter()
+                        .filter(|output| output.value.input() == input.id)
+                        .map(|output| output.path.clone())
+                        .collect(),
+                    secrets,
+                }
+            })
+            .collect())
+    }
+
+    /// Report which outputs would change if the given properties of the
+    /// collection item `id` change, e.g. when it is renamed. An empty
+    /// result means deployments using these inputs don't need to be
+    /// reinstalled.
+    pub fn collection_item_impact<C>(
+        &self,
+        inputs: &serde_json::Value,
+        collection: &C,
+        id: &str,
+        properties: &[&str],
+    ) -> Result<UiSchemaCollectionItemImpact, serde_json::Error>
+    where
+        C: UiSchemaCollections,
+    {
+        let collection_value = serde_json::to_value(collection)?;
+        let (visible, _) = self.rendered_visibility(inputs);
+        let is_affected = |input_ref: &UiSchemaInputRef| {
+            let UiSchemaInputRef::FieldProperty(fp) = input_ref else {
+                return false;
             };
-            used_collection == Some(&collection_value) && inputs[&input.id] == id
-        })
+            properties.contains(&fp.property.as_str())
+                && self
+                    .get_inputs()
+                    .iter()
+                    .zip(visible.iter())
+                    .any(|(input, visible)| {
+                        input.id == fp.input
+                            && matches!(
+                                &input.input_type.single_type,
+                                UiSchemaInputSingleType::CollectionSelect { collection }
+                                    if *collection == collection_value
+                            )
+                            && input.selects_item(inputs, id)
+                            && *visible
+                    })
+        };
+
+        let outputs = self.get_outputs();
+        let mut impact = UiSchemaCollectionItemImpact {
+            output_paths: outputs
+                .values
+                .iter()
+                .filter(|output| is_affected(&output.value))
+                .map(|output| output.path.clone())
+                .collect(),
+            secrets: Default::default(),
+        };
+        for (secret_name, attrs_schema) in outputs.secrets.0.iter() {
+            let mut keys: Vec<String> = attrs_schema
+                .iter()
+                .filter(|(_, attr_schema)| is_affected(attr_schema))
+                .map(|(key, _)| key.clone())
+                .collect();
+            if !keys.is_empty() {
+                keys.sort();
+                impact.secrets.insert(secret_name.clone(), keys);
+            }
+        }
+        Ok(impact)
+    }
+
+    fn get_collection_input<C>(
+        &self,
+        input_id: &str,
+    ) -> Result<(&UiSchemaInput, C), UiSchemaInputError<C::Error>>
+    where
+        C: UiSchemaCollections,
+    {
+        let schema = UiSchemaInputRef::get_input_schema::<C>(self.get_inputs(), input_id)?;
+        let collections = UiSchemaInputRef::get_collection::<C>(schema)?
+            .ok_or_else(|| UiSchemaInputError::InputNotACollection(input_id.to_owned()))?;
+        Ok((schema, collections))
+    }
+
+    pub async fn list_collection_items<C>(
+        &self,
+        env_id: Uuid,
+        input_id: &str,
+        inputs: &serde_json::Value,
+    ) -> Result<Vec<String>, UiSchemaInputError<C::Error>>
+    where
+        C: UiSchemaCollections,
+    {
+        let (schema, collections) = self.get_collection_input::<C>(input_id)?;
+        match schema.resolve_filters::<C>(&self.rendered_inputs(inputs)) {
+            Ok(filters) => collections.list(env_id, &filters).await,
+            Err(UiSchemaInputError::FilterInputMissing(..)) => Ok(Vec::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Search the items offered by a `CollectionSelect` input, applying
+    /// its filters. Used for serving dropdown options of any collection
+    /// appearing in a schema. No items are offered while an input the
+    /// filters refer to is unset or hidden.
+    pub async fn search_collection_items<C>(
+        &self,
+        env_id: Uuid,
+        input_id: &str,
+        inputs: &serde_json::Value,
+        query: &UiSchemaCollectionQuery,
+    ) -> Result<UiSchemaCollectionPage, UiSchemaInputError<C::Error>>
+    where
+        C: UiSchemaCollections,
+    {
+        let (schema, collections) = self.get_collection_input::<C>(input_id)?;
+        match schema.resolve_filters::<C>(&self.rendered_inputs(inputs)) {
+            Ok(filters) => collections.search(env_id, &filters, query).await,
+            Err(UiSchemaInputError::FilterInputMissing(..)) => Ok(UiSchemaCollectionPage {
+                items: Vec::new(),
+                page: query.page,
+                per_page: query.per_page(),
+                num_total: 0,
+            }),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Get the inputs currently visible to the user. Hidden inputs are
+    /// treated as missing when evaluating other inputs' conditions, so
+    /// inputs depending on a hidden input may be hidden too, as are the
+    /// inputs of a hidden layout section. Inputs in a dependency cycle are
+    /// never visible, see `analyze_visibility`.
+    pub fn visible_inputs(
+        &self,
+        inputs: &serde_json::Value,
+    ) -> Result<Vec<&UiSchemaInput>, UiSchemaVisibilityError> {
+        let cyclic = crate::visibility::cyclic_inputs(self.get_inputs(), self.get_layout());
+        let (visible, _, settled) =
+            self.resolve_visibility(inputs, &cyclic, |input, section, inputs| {
+                Ok(input.evaluate_visibility(inputs)?
+                    && section.map_or(Ok(true), |section| section.evaluate_visibility(inputs))?)
+            })?;
+        if !settled {
+            return Err(UiSchemaVisibilityError::NotConverging);
+        }
+        Ok(self
+            .get_inputs()
+            .iter()
+            .zip(visible)
+            .filter_map(|(input, visible)| visible.then_some(input))
+            .collect())
+    }
+
+    /// `inputs` without the values of hidden inputs, as used for
+    /// rendering. Unlike `visible_inputs`, conditions failing to evaluate
+    /// hide their input instead of failing. Inputs in a dependency cycle
+    /// are hidden, the same as in `visible_inputs`.
+    fn rendered_inputs(&self, inputs: &serde_json::Value) -> serde_json::Value {
+        self.rendered_visibility(inputs).1
+    }
+
+    /// The visibility of each input as used for rendering, along with
+    /// `rendered_inputs`
+    fn rendered_visibility(&self, inputs: &serde_json::Value) -> (Vec<bool>, serde_json::Value) {
+        let cyclic = crate::visibility::cyclic_inputs(self.get_inputs(), self.get_layout());
+        let (visible, rendered_inputs, _) = self
+            .resolve_visibility(inputs, &cyclic, |input, section, inputs| {
+                Ok::<_, std::convert::Infallible>(
+                    input.is_shown(inputs)
+                        && section.is_none_or(|section| section.is_shown(inputs)),
+                )
+            })
+            .unwrap_or_else(|never| match never {});
+        (visible, rendered_inputs)
+    }
+
+    /// Evaluate the visibility of all inputs using `evaluate`, which is
+    /// given each input along with the layout section containing it,
+    /// returning it along with `inputs` without the values of hidden
+    /// inputs, and whether it settled. Inputs whose visibility doesn't
+    /// settle are hidden, as are inputs marked in `hidden`.
+    fn resolve_visibility<E>(
+        &self,
+        inputs: &serde_json::Value,
+        hidden: &[bool],
+        evaluate: impl Fn(
+            &UiSchemaInput,
+            Option<&UiSchemaSection>,
+            &serde_json::Value,
+        ) -> Result<bool, E>,
+    ) -> Result<(Vec<bool>, serde_json::Value, bool), E> {
+        let schema_inputs = self.get_inputs();
+        let sections: Vec<Option<&UiSchemaSection>> = schema_inputs
+            .iter()
+            .map(|input| {
+                self.get_layout()
+                    .and_then(|layout| layout.section_of(&input.id))
+            })
+            .collect();
+        let mut visible: Vec<bool> = hidden.iter().map(|hidden| !hidden).collect();
+        let mut previous = visible.clone();
+        // Hiding an input can change the visibility of inputs depending on
+        // it, so repeat until nothing changes. Each round can only settle
+        // more inputs, unless the conditions oscillate through variables
+        // computed at runtime.
+        for _ in 0..=schema_inputs.len() {
+            let effective_inputs = without_hidden(inputs, schema_inputs, &visible);
+            let new_visible = schema_inputs
+                .iter()
+                .zip(sections.iter())
+                .zip(hidden.iter())
+                .map(|((input, section), hidden)| {
+                    Ok(!hidden && evaluate(input, *section, &effective_inputs)?)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if new_visible == visible {
+                return Ok((visible, effective_inputs, true));
+            }
+            previous = std::mem::replace(&mut visible, new_visible);
+        }
+        let visible: Vec<bool> = visible
+            .into_iter()
+            .zip(previous)
+            .map(|(visible, previous)| visible && previous)
+            .collect();
+        let effective_inputs = without_hidden(inputs, schema_inputs, &visible);
+        Ok((visible, effective_inputs, false))
+    }
+
+    /// Verify the filters of the inputs referenced by `input_refs`, once
+    /// per input, instead of once per output using it.
+    async fn verify_filters<'a, C>(
+        &self,
+        env_id: Uuid,
+        inputs: &serde_json::Value,
+        hidden: &BTreeSet<&str>,
+        input_refs: impl Iterator<Item = &'a UiSchemaInputRef>,
+    ) -> Result<(), UiSchemaInputError<C::Error>>
+    where
+        C: UiSchemaCollections,
+    {
+        let mut verified = BTreeSet::new();
+        for input_ref in input_refs {
+            if verified.insert(input_ref.input()) {
+                UiSchemaInputRef::verify_input_filters::<C>(
+                    env_id,
+                    self.get_inputs(),
+                    inputs,
+                    hidden,
+                    input_ref.input(),
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the schema is consistent with the collections it uses,
+    /// see `UiSchemaV0::validate`.
+    pub fn validate<C>(&self) -> Result<(), UiSchemaValidationError>
+    where
+        C: UiSchemaCollections,
+    {
+        match self {
+            Self::V1Beta1(v1) => v1.inner.validate::<C>(),
+            Self::V0(v0) => v0.validate::<C>(),
+        }
     }
 
     pub async fn get_values<C>(
@@ -61,11 +391,23 @@ impl UiSchema {
         C: UiSchemaCollections,
     {
         let schema_inputs = self.get_inputs();
+        let (visible, inputs) = self.rendered_visibility(inputs);
+        let hidden = hidden_ids(schema_inputs, &visible);
+        let outputs = &self.get_outputs().values;
+        self.verify_filters::<C>(
+            env_id,
+            &inputs,
+            &hidden,
+            outputs.iter().map(|output| &output.value),
+        )
+        .await?;
         let mut values = Map::new();
-        for output in self.get_outputs().values.iter() {
-            output
-                .resolve_into::<C>(env_id, schema_inputs, inputs, &mut values)
-                .await?;
+        for output in outputs.iter() {
+            let resolved = output
+                .value
+                .resolve_verified::<C>(env_id, schema_inputs, &inputs, &hidden)
+                .await;
+            output.insert_resolved(resolved, &mut values)?;
         }
         Ok(values)
     }
@@ -80,11 +422,21 @@ impl UiSchema {
     {
         let mut result: Vec<RenderedSecret> = Vec::new();
         let schema_inputs = self.get_inputs();
-        for (secret_name, attrs_schema) in self.get_outputs().secrets.0.iter() {
+        let (visible, inputs) = self.rendered_visibility(inputs);
+        let hidden = hidden_ids(schema_inputs, &visible);
+        let secrets = &self.get_outputs().secrets.0;
+        self.verify_filters::<C>(
+            env_id,
+            &inputs,
+            &hidden,
+            secrets.values().flat_map(|attrs| attrs.values()),
+        )
+        .await?;
+        for (secret_name, attrs_schema) in secrets.iter() {
             let mut attrs: BTreeMap<String, String> = Default::default();
             for (key, attr_schema) in attrs_schema.iter() {
                 let value = match attr_schema
-                    .resolve::<C>(env_id, schema_inputs, inputs)
+                    .resolve_verified::<C>(env_id, schema_inputs, &inputs, &hidden)
                     .await
                 {
                     Ok(x) => x,
@@ -110,13 +462,229 @@ impl UiSchema {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+fn without_hidden(
+    inputs: &serde_json::Value,
+    schema_inputs: &[UiSchemaInput],
+    visible: &[bool],
+) -> serde_json::Value {
+    let mut effective_inputs = inputs.clone();
+    if let Some(effective_map) = effective_inputs.as_object_mut() {
+        for (input, _) in schema_inputs
+            .iter()
+            .zip(visible.iter())
+            .filter(|(_, visible)| !**visible)
+        {
+            effective_map.remove(&input.id);
+        }
+    }
+    effective_inputs
+}
+
+/// IDs of the inputs not marked in `visible`
+fn hidden_ids<'a>(schema_inputs: &'a [UiSchemaInput], visible: &[bool]) -> BTreeSet<&'a str> {
+    schema_inputs
+        .iter()
+        .zip(visible.iter())
+        .filter(|(_, visible)| !**visible)
+        .map(|(input, _)| input.id.as_str())
+        .collect()
+}
+
+/// An input selecting a specific collection item
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct UiSchemaCollectionReference {
+    pub input: String,
+    /// False if the input is hidden, so its value is not rendered
+    pub visible: bool,
+    pub output_paths: Vec<Vec<String>>,
+    /// Secret names, each with the keys the input's value is rendered into
+    pub secrets: BTreeMap<String, Vec<String>>,
+}
+
+/// Outputs affected by a change to a collection item
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct UiSchemaCollectionItemImpact {
+    pub output_paths: Vec<Vec<String>>,
+    /// Affected secret names, each with its affected keys
+    pub secrets: BTreeMap<String, Vec<String>>,
+}
+
+impl UiSchemaCollectionItemImpact {
+    pub fn is_empty(&self) -> bool {
+        self.output_paths.is_empty() && self.secrets.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(deny_unknown_fields)]
 pub struct UiSchemaV0 {
     pub inputs: Vec<UiSchemaInput>,
     #[serde(default)]
     pub outputs: UiSchemaOutputs,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<UiSchemaLayout>,
+}
+
+/// How the frontend arranges inputs in sections. Each input appears in
+/// at most one section. Inputs in a section hidden by its `showIf` are
+/// hidden too, so they're treated like any other hidden input when
+/// rendering and normalizing.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UiSchemaLayout {
+    #[serde(default)]
+    pub mode: UiSchemaLayoutMode,
+    /// Sections in display order
+    pub sections: Vec<UiSchemaSection>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum UiSchemaLayoutMode {
+    /// All sections are displayed one after the other
+    #[default]
+    Sections,
+    /// Each section is displayed in its own tab
+    Tabs,
+    /// Sections are wizard steps, filled in order
+    Wizard,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UiSchemaSection {
+    pub id: String,
+    pub title: LocalizedString,
+    #[serde(default)]
+    pub description: Option<LocalizedString>,
+    /// IDs of inputs in this section, in display order
+    pub inputs: Vec<String>,
+    /// Advanced sections are collapsible and collapsed by default
+    #[serde(default)]
+    pub advanced: bool,
+    #[serde(default)]
+    pub show_if: Option<serde_json::Value>,
+}
+
+impl UiSchemaLayout {
+    /// The section containing the input `input_id`, if any
+    pub fn section_of(&self, input_id: &str) -> Option<&UiSchemaSection> {
+        self.sections
+            .iter()
+            .find(|section| section.inputs.iter().any(|id| id == input_id))
+    }
+}
+
+impl UiSchemaSection {
+    fn is_shown(&self, inputs: &serde_json::Value) -> bool {
+        self.evaluate_visibility(inputs).unwrap_or(false)
+    }
+
+    /// Evaluate this section's own `showIf` condition
+    pub fn evaluate_visibility(
+        &self,
+        inputs: &serde_json::Value,
+    ) -> Result<bool, UiSchemaVisibilityError> {
+        match self.show_if.as_ref() {
+            Some(show_if) => match juspay_jsonlogic::apply(show_if, inputs) {
+                Ok(serde_json::Value::Bool(shown)) => Ok(shown),
+                Ok(other) => Err(UiSchemaVisibilityError::SectionShowIfNotBool(
+                    self.id.clone(),
+                    other,
+                )),
+                Err(err) => Err(UiSchemaVisibilityError::SectionShowIfError(
+                    self.id.clone(),
+                    err,
+                )),
+            },
+            None => Ok(true),
+        }
+    }
+}
+
+impl UiSchemaV0 {
+    pub fn localize(&mut self, locale: &str) {
+        for input in self.inputs.iter_mut() {
+            input.localize(locale);
+        }
+        for section in self
+            .layout
+            .iter_mut()
+            .flat_map(|layout| layout.sections.iter_mut())
+        {
+            section.title.localize(locale);
+            if let Some(description) = section.description.as_mut() {
+                description.localize(locale);
+            }
+        }
+    }
+
+    /// Check that every collection named by an input is known, that
+    /// collection filters and every output referring to an input or a
+    /// collection property refer to one that exists, and that layout
+    /// sections only contain known inputs, each in a single section.
+    /// Properties are only checked for collections declaring them, see
+    /// `UiSchemaCollections::properties`.
+    pub fn validate<C>(&self) -> Result<(), UiSchemaValidationError>
+    where
+        C: UiSchemaCollections,
+    {
+        for input in self.inputs.iter() {
+            if let UiSchemaInputSingleType::CollectionSelect { collection } =
+                &input.input_type.single_type
+            {
+                parse_collection::<C>(collection).map_err(|err| {
+                    UiSchemaValidationError::InvalidCollectionName(collection.to_owned(), err)
+                })?;
+            }
+            for filter in input.filters.iter().flatten() {
+                if let UiSchemaInputFilterValue::Input {
+                    input: filter_input,
+                } = &filter.value
+                {
+                    if !self.inputs.iter().any(|input| input.id == *filter_input) {
+                        return Err(UiSchemaValidationError::UnknownFilterInput(
+                            input.id.clone(),
+                            filter_input.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+        for input_ref in self.outputs.input_refs() {
+            input_ref.validate::<C>(&self.inputs)?;
+        }
+        let mut section_inputs = BTreeMap::new();
+        for section in self.layout.iter().flat_map(|layout| layout.sections.iter()) {
+            for input_id in section.inputs.iter() {
+                if !self.inputs.iter().any(|input| input.id == *input_id) {
+                    return Err(UiSchemaValidationError::UnknownSectionInput(
+                        section.id.clone(),
+                        input_id.clone(),
+                    ));
+                }
+                if let Some(first) = section_inputs.insert(input_id, &section.id) {
+                    return Err(UiSchemaValidationError::DuplicateSectionInput(
+                        input_id.clone(),
+                        first.clone(),
+                        section.id.clone(),
+                    ));
+                }
+            }
+        }
+        if let Some(issue) = analyze_visibility(&self.inputs, self.layout.as_ref())
+            .into_iter()
+            .next()
+        {
+            return Err(issue.into());
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -129,7 +697,7 @@ pub struct UiSchemaV1Beta1 {
     pub inner: UiSchemaV0,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, EnumString, EnumDiscriminants)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, EnumString, EnumDiscriminants)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[strum_discriminants(derive(EnumString, strum::Display))]
 #[strum_discriminants(strum(ascii_case_insensitive))]
@@ -146,7 +714,7 @@ pub enum UiSchemaInputSingleType {
     Checkbox,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(try_from = "SerializedUiSchemaInputType")]
 #[serde(into = "SerializedUiSchemaInputType")]
@@ -155,6 +723,22 @@ pub struct UiSchemaInputType {
     pub is_array: bool,
 }
 
+impl UiSchemaInputType {
+    pub fn single(single_type: UiSchemaInputSingleType) -> Self {
+        Self {
+            single_type,
+            is_array: false,
+        }
+    }
+
+    pub fn array(single_type: UiSchemaInputSingleType) -> Self {
+        Self {
+            single_type,
+            is_array: true,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
@@ -227,8 +811,8 @@ impl From<UiSchemaInputType> for SerializedUiSchemaInputType {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct UiSchemaFieldValuePair {
-    field: String,
-    value: serde_json::Value,
+    pub field: String,
+    pub value: serde_json::Value,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -239,11 +823,11 @@ pub struct UiSchemaInput {
     #[serde(flatten)]
     #[cfg_attr(feature = "utoipa", schema(value_type = SerializedUiSchemaInputType))]
     pub input_type: UiSchemaInputType, // Parsed from actual fields: type, item_type and collection, see SerializedUiSchemaInputType
-    label: String,
+    label: LocalizedString,
     #[serde(default)]
     initial_value: Option<serde_json::Value>,
     #[serde(default)]
-    help_text: Option<String>,
+    help_text: Option<LocalizedString>,
     #[serde(default)]
     pub required: bool,
     #[serde(default)]
@@ -255,13 +839,17 @@ pub struct UiSchemaInput {
     #[serde(default)]
     show_if: Option<serde_json::Value>,
     #[serde(default)]
-    filters: Option<Vec<UiSchemaInputFieldValue>>,
+    filters: Option<Vec<UiSchemaInputFilter>>,
     #[serde(default)]
     minimum: Option<Decimal>,
     #[serde(default)]
     maximum: Option<Decimal>,
     #[serde(default)]
     step: Option<Decimal>,
+    /// Previous IDs of this input, used for migrating stored inputs
+    /// when upgrading to a chart version where this input was renamed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub renamed_from: Vec<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -270,9 +858,9 @@ pub struct UiSchemaInput {
 pub struct UiSchemaInputFieldOption {
     pub value: serde_json::Value,
     #[serde(default)]
-    pub label: Option<String>,
+    pub label: Option<LocalizedString>,
     #[serde(default)]
-    pub help_text: Option<String>,
+    pub help_text: Option<LocalizedString>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -282,6 +870,329 @@ pub struct UiSchemaInputFieldValue {
     pub value: serde_json::Value,
 }
 
+/// A filter applied to the items of a `CollectionSelect` input. The
+/// value is either static, or taken from another input, for example to
+/// only show databases in the selected cluster:
+///
+/// ```yaml
+/// filters:
+///   - field: cluster_id
+///     input: cluster
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct UiSchemaInputFilter {
+    pub field: String,
+    #[serde(flatten)]
+    pub value: UiSchemaInputFilterValue,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(untagged)]
+pub enum UiSchemaInputFilterValue {
+    Value { value: serde_json::Value },
+    Input { input: String },
+}
+
+impl UiSchemaInput {
+    pub fn localize(&mut self, locale: &str) {
+        self.label.localize(locale);
+        if let Some(help_text) = self.help_text.as_mut() {
+            help_text.localize(locale);
+        }
+        for option in self.options.iter_mut().flatten() {
+            if let Some(label) = option.label.as_mut() {
+                label.localize(locale);
+            }
+            if let Some(help_text) = option.help_text.as_mut() {
+                help_text.localize(locale);
+            }
+        }
+    }
+
+    /// Whether this input is shown according to its own `showIf` or
+    /// `showIfAll` condition. Evaluation errors hide the input.
+    fn is_shown(&self, inputs: &serde_json::Value) -> bool {
+        self.evaluate_visibility(inputs).unwrap_or(false)
+    }
+
+    /// Evaluate this input's own `showIf` or `showIfAll` condition,
+    /// without considering whether the inputs it depends on are visible.
+    pub fn evaluate_visibility(
+        &self,
+        inputs: &serde_json::Value,
+    ) -> Result<bool, UiSchemaVisibilityError> {
+        if let Some(show_if) = self.show_if.as_ref() {
+            match juspay_jsonlogic::apply(show_if, inputs) {
+                Ok(serde_json::Value::Bool(shown)) => Ok(shown),
+                Ok(other) => Err(UiSchemaVisibilityError::ShowIfNotBool(
+                    self.id.clone(),
+                    other,
+                )),
+                Err(err) => Err(UiSchemaVisibilityError::ShowIfError(self.id.clone(), err)),
+            }
+        } else if let Some(show_if_all) = self.show_if_all.as_ref() {
+            Ok(show_if_all
+                .iter()
+                .all(|fv| inputs.get(&fv.field) == Some(&fv.value)))
+        } else {
+            Ok(true)
+        }
+    }
+
+    /// Whether the value of this input selects the collection item `id`,
+    /// either directly or as one of the items of an array input.
+    fn selects_item(&self, inputs: &serde_json::Value, id: &str) -> bool {
+        match inputs.get(&self.id) {
+            Some(serde_json::Value::String(value)) => value == id,
+            Some(serde_json::Value::Array(values)) if self.input_type.is_array => {
+                values.iter().any(|value| value == id)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether this input has any collection filters
+    fn has_filters(&self) -> bool {
+        self.filters
+            .as_ref()
+            .is_some_and(|filters| !filters.is_empty())
+    }
+
+    /// Resolve the collection filters of this input into static
+    /// field/value pairs, taking the value of referenced inputs. Fails
+    /// with `FilterInputMissing` when a referenced input has no value,
+    /// in which case no collection items match the filters.
+    pub fn resolve_filters<C>(
+        &self,
+        inputs: &serde_json::Value,
+    ) -> Result<Vec<UiSchemaInputFieldValue>, UiSchemaInputError<C::Error>>
+    where
+        C: UiSchemaCollections,
+    {
+        self.filters
+            .iter()
+            .flatten()
+            .map(|filter| {
+                let value = match &filter.value {
+                    UiSchemaInputFilterValue::Value { value } => value.clone(),
+                    UiSchemaInputFilterValue::Input { input } => match inputs.get(input) {
+                        None | Some(serde_json::Value::Null) => {
+                            return Err(UiSchemaInputError::FilterInputMissing(
+                                self.id.clone(),
+                                input.to_owned(),
+                            ))
+                        }
+                        Some(value) => value.clone(),
+                    },
+                };
+                Ok(UiSchemaInputFieldValue {
+                    field: filter.field.clone(),
+                    value,
+                })
+            })
+            .collect()
+    }
+}
+
+impl UiSchemaInput {
+    pub fn builder(
+        id: impl Into<String>,
+        input_type: UiSchemaInputType,
+        label: impl Into<LocalizedString>,
+    ) -> UiSchemaInputBuilder {
+        UiSchemaInputBuilder {
+            input: Self {
+                id: id.into(),
+                input_type,
+                label: label.into(),
+                initial_value: None,
+                help_text: None,
+                required: false,
+                sensitive: false,
+                options: None,
+                show_if_all: None,
+                show_if: None,
+                filters: None,
+                minimum: None,
+                maximum: None,
+                step: None,
+                renamed_from: Vec::new(),
+            },
+        }
+    }
+
+    pub fn label(&self) -> &LocalizedString {
+        &self.label
+    }
+
+    pub fn initial_value(&self) -> Option<&serde_json::Value> {
+        self.initial_value.as_ref()
+    }
+
+    pub fn help_text(&self) -> Option<&LocalizedString> {
+        self.help_text.as_ref()
+    }
+
+    pub fn show_if_all(&self) -> Option<&[UiSchemaFieldValuePair]> {
+        self.show_if_all.as_deref()
+    }
+
+    pub fn show_if(&self) -> Option<&serde_json::Value> {
+        self.show_if.as_ref()
+    }
+
+    pub fn filters(&self) -> Option<&[UiSchemaInputFilter]> {
+        self.filters.as_deref()
+    }
+
+    pub fn minimum(&self) -> Option<Decimal> {
+        self.minimum
+    }
+
+    pub fn maximum(&self) -> Option<Decimal> {
+        self.maximum
+    }
+
+    pub fn step(&self) -> Option<Decimal> {
+        self.step
+    }
+}
+
+/// Builds a `UiSchemaInput` in code, see `UiSchemaInput::builder`
+#[derive(Clone, Debug)]
+pub struct UiSchemaInputBuilder {
+    input: UiSchemaInput,
+}
+
+impl UiSchemaInputBuilder {
+    pub fn initial_value(mut self, initial_value: serde_json::Value) -> Self {
+        self.input.initial_value = Some(initial_value);
+        self
+    }
+
+    pub fn help_text(mut self, help_text: impl Into<LocalizedString>) -> Self {
+        self.input.help_text = Some(help_text.into());
+        self
+    }
+
+    pub fn required(mut self, required: bool) -> Self {
+        self.input.required = required;
+        self
+    }
+
+    pub fn sensitive(mut self, sensitive: bool) -> Self {
+        self.input.sensitive = sensitive;
+        self
+    }
+
+    pub fn option(mut self, option: UiSchemaInputFieldOption) -> Self {
+        self.input.options.get_or_insert_with(Vec::new).push(option);
+        self
+    }
+
+    pub fn show_if_all(mut self, field: impl Into<String>, value: serde_json::Value) -> Self {
+        self.input
+            .show_if_all
+            .get_or_insert_with(Vec::new)
+            .push(UiSchemaFieldValuePair {
+                field: field.into(),
+                value,
+            });
+        self
+    }
+
+    pub fn show_if(mut self, show_if: serde_json::Value) -> Self {
+        self.input.show_if = Some(show_if);
+        self
+    }
+
+    pub fn filter(mut self, filter: UiSchemaInputFilter) -> Self {
+        self.input.filters.get_or_insert_with(Vec::new).push(filter);
+        self
+    }
+
+    pub fn minimum(mut self, minimum: Decimal) -> Self {
+        self.input.minimum = Some(minimum);
+        self
+    }
+
+    pub fn maximum(mut self, maximum: Decimal) -> Self {
+        self.input.maximum = Some(maximum);
+        self
+    }
+
+    pub fn step(mut self, step: Decimal) -> Self {
+        self.input.step = Some(step);
+        self
+    }
+
+    pub fn renamed_from(mut self, id: impl Into<String>) -> Self {
+        self.input.renamed_from.push(id.into());
+        self
+    }
+
+    pub fn build(self) -> UiSchemaInput {
+        self.input
+    }
+}
+
+/// Builds a `UiSchema` in code, e.g. for charts generated by other
+/// services. Produces the latest schema version.
+#[derive(Clone, Debug, Default)]
+pub struct UiSchemaBuilder {
+    inner: UiSchemaV0,
+}
+
+impl UiSchemaBuilder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn input(mut self, input: UiSchemaInput) -> Self {
+        self.inner.inputs.push(input);
+        self
+    }
+
+    pub fn output_value(mut self, path: &[&str], value: UiSchemaInputRef) -> Self {
+        self.inner.outputs.values.push(UiSchemaOutputValue {
+            path: path.iter().map(ToString::to_string).collect(),
+            value,
+        });
+        self
+    }
+
+    pub fn output_secret(
+        mut self,
+        secret_name: impl Into<String>,
+        key: impl Into<String>,
+        value: UiSchemaInputRef,
+    ) -> Self {
+        self.inner
+            .outputs
+            .secrets
+            .0
+            .entry(secret_name.into())
+            .or_default()
+            .insert(key.into(), value);
+        self
+    }
+
+    pub fn layout(mut self, layout: UiSchemaLayout) -> Self {
+        self.inner.layout = Some(layout);
+        self
+    }
+
+    pub fn build(self) -> UiSchema {
+        UiSchema::V1Beta1(UiSchemaV1Beta1 {
+            api_version: Default::default(),
+            kind: Default::default(),
+            inner: self.inner,
+        })
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct UiSchemaOutputSecrets(pub HashMap<String, HashMap<String, UiSchemaInputRef>>);
@@ -294,6 +1205,18 @@ pub struct UiSchemaOutputs {
     pub secrets: UiSchemaOutputSecrets,
 }
 
+impl UiSchemaOutputs {
+    /// All input references used by this schema, in values and in secrets
+    pub fn input_refs(&self) -> impl Iterator<Item = &UiSchemaInputRef> {
+        self.values.iter().map(|output| &output.value).chain(
+            self.secrets
+                .0
+                .values()
+                .flat_map(|attrs_schema| attrs_schema.values()),
+        )
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub enum UiSchemaInputRef {
@@ -315,6 +1238,27 @@ pub struct UiSchemaInputRefProperty {
 }
 
 impl UiSchemaInputRef {
+    pub fn field_value(input: impl Into<String>) -> Self {
+        Self::FieldValue(UiSchemaInputRefField {
+            input: input.into(),
+        })
+    }
+
+    pub fn field_property(input: impl Into<String>, property: impl Into<String>) -> Self {
+        Self::FieldProperty(UiSchemaInputRefProperty {
+            input: input.into(),
+            property: property.into(),
+        })
+    }
+
+    /// The ID of the referenced input
+    pub fn input(&self) -> &str {
+        match self {
+            Self::FieldValue(fv) => &fv.input,
+            Self::FieldProperty(fp) => &fp.input,
+        }
+    }
+
     fn get_input_schema<'a, C>(
         input_schema: &'a [UiSchemaInput],
         id: &str,
@@ -328,26 +1272,19 @@ impl UiSchemaInputRef {
             .ok_or_else(|| UiSchemaInputError::MissingInputSchema(id.to_owned()))
     }
 
+    /// The value of the input `id`, unless it's hidden by its own
+    /// condition or is one of the `hidden` inputs.
     fn get_input<C>(
         schema: &UiSchemaInput,
         inputs: &serde_json::Value,
+        hidden: &BTreeSet<&str>,
         id: &str,
     ) -> Result<serde_json::Value, UiSchemaInputError<C::Error>>
     where
         C: UiSchemaCollections,
     {
-        if let Some(show_if) = schema.show_if.as_ref() {
-            let res = juspay_jsonlogic::apply(show_if, inputs);
-            if !matches!(res, Ok(serde_json::Value::Bool(true))) {
-                return Err(UiSchemaInputError::OptionalInputMissing(id.to_owned()));
-            }
-        } else if let Some(show_if_all) = schema.show_if_all.as_ref() {
-            if show_if_all
-                .iter()
-                .any(|fv| inputs.get(&fv.field) != Some(&fv.value))
-            {
-                return Err(UiSchemaInputError::OptionalInputMissing(id.to_owned()));
-            }
+        if hidden.contains(id) || !schema.is_shown(inputs) {
+            return Err(UiSchemaInputError::OptionalInputMissing(id.to_owned()));
         }
         Ok(inputs
             .get(id)
@@ -361,63 +1298,211 @@ impl UiSchemaInputRef {
             .clone())
     }
 
+    fn get_collection<C>(schema: &UiSchemaInput) -> Result<Option<C>, UiSchemaInputError<C::Error>>
+    where
+        C: UiSchemaCollections,
+    {
+        match &schema.input_type.single_type {
+            UiSchemaInputSingleType::CollectionSelect { collection } => {
+                parse_collection::<C>(collection).map(Some).map_err(|err| {
+                    UiSchemaInputError::InvalidCollectionName(collection.to_owned(), err)
+                })
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn get_ids<'a, C>(
+        schema: &UiSchemaInput,
+        id_value: &'a serde_json::Value,
+    ) -> Result<Vec<&'a str>, UiSchemaInputError<C::Error>>
+    where
+        C: UiSchemaCollections,
+    {
+        if schema.input_type.is_array {
+            id_value
+                .as_array()
+                .ok_or_else(|| UiSchemaInputError::InputNotStringArray(schema.id.clone()))?
+                .iter()
+                .map(|id_value| {
+                    id_value
+                        .as_str()
+                        .ok_or_else(|| UiSchemaInputError::InputNotStringArray(schema.id.clone()))
+                })
+                .collect()
+        } else {
+            Ok(vec![id_value.as_str().ok_or_else(|| {
+                UiSchemaInputError::InputNotString(schema.id.clone())
+            })?])
+        }
+    }
+
+    /// Make sure every selected collection item satisfies the input's
+    /// filters, so inputs can't select items the UI wouldn't offer. No
+    /// items are offered while an input the filters refer to is missing.
+    /// Collections not supporting `list` can't be filtered, so their
+    /// items are not verified.
+    async fn verify_filters<C>(
+        collections: &C,
+        env_id: Uuid,
+        schema: &UiSchemaInput,
+        inputs: &serde_json::Value,
+        ids: &[&str],
+    ) -> Result<(), UiSchemaInputError<C::Error>>
+    where
+        C: UiSchemaCollections,
+    {
+        if !schema.has_filters() {
+            return Ok(());
+        }
+        let matching = match schema.resolve_filters::<C>(inputs) {
+            Ok(filters) => collections.filter_items(env_id, ids, &filters).await,
+            Err(UiSchemaInputError::FilterInputMissing(..)) => Ok(Vec::new()),
+            Err(err) => Err(err),
+        };
+        let matching = match matching {
+            Ok(matching) => matching,
+            Err(UiSchemaInputError::UnsupportedCollection(_)) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        match ids.iter().find(|id| !matching.iter().any(|m| m == *id)) {
+            Some(id) => Err(UiSchemaInputError::CollectionItemFilteredOut(
+                schema.id.clone(),
+                id.to_string(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub fn validate<C>(&self, input_schema: &[UiSchemaInput]) -> Result<(), UiSchemaValidationError>
+    where
+        C: UiSchemaCollections,
+    {
+        match self {
+            Self::FieldValue(fv) => input_schema
+                .iter()
+                .find(|i| i.id == fv.input)
+                .map(|_| ())
+                .ok_or_else(|| UiSchemaValidationError::MissingInputSchema(fv.input.clone())),
+            Self::FieldProperty(fp) => {
+                let schema = input_schema
+                    .iter()
+                    .find(|i| i.id == fp.input)
+                    .ok_or_else(|| UiSchemaValidationError::MissingInputSchema(fp.input.clone()))?;
+                let UiSchemaInputSingleType::CollectionSelect { collection } =
+                    &schema.input_type.single_type
+                else {
+                    return Err(UiSchemaValidationError::InputNotACollection(
+                        fp.input.clone(),
+                    ));
+                };
+                let collections = parse_collection::<C>(collection).map_err(|err| {
+                    UiSchemaValidationError::InvalidCollectionName(collection.to_owned(), err)
+                })?;
+                match collections.properties() {
+                    Some(properties) if !properties.iter().any(|p| p.name == fp.property) => {
+                        Err(UiSchemaValidationError::UnknownProperty(
+                            fp.property.clone(),
+                            collections.to_string(),
+                        ))
+                    }
+                    _ => Ok(()),
+                }
+            }
+        }
+    }
+
     pub async fn resolve<C>(
         &self,
         env_id: Uuid,
         input_schema: &[UiSchemaInput],
         inputs: &serde_json::Value,
     ) -> Result<serde_json::Value, UiSchemaInputError<C::Error>>
+    where
+        C: UiSchemaCollections,
+    {
+        let hidden = BTreeSet::new();
+        Self::verify_input_filters::<C>(env_id, input_schema, inputs, &hidden, self.input())
+            .await?;
+        self.resolve_verified::<C>(env_id, input_schema, inputs, &hidden)
+            .await
+    }
+
+    /// Verify the filters of the input `id`, see `verify_filters`.
+    /// Problems with the input itself are left to be reported when
+    /// resolving it.
+    async fn verify_input_filters<C>(
+        env_id: Uuid,
+        input_schema: &[UiSchemaInput],
+        inputs: &serde_json::Value,
+        hidden: &BTreeSet<&str>,
+        id: &str,
+    ) -> Result<(), UiSchemaInputError<C::Error>>
+    where
+        C: UiSchemaCollections,
+    {
+        let Ok(schema) = Self::get_input_schema::<C>(input_schema, id) else {
+            return Ok(());
+        };
+        if !schema.has_filters() {
+            return Ok(());
+        }
+        let Ok(Some(collections)) = Self::get_collection::<C>(schema) else {
+            return Ok(());
+        };
+        let Ok(value) = Self::get_input::<C>(schema, inputs, hidden, id) else {
+            return Ok(());
+        };
+        let ids = Self::get_ids::<C>(schema, &value)?;
+        Self::verify_filters(&collections, env_id, schema, inputs, &ids).await
+    }
+
+    /// Same as `resolve`, for inputs whose filters were already verified,
+    /// treating the `hidden` inputs as hidden regardless of their own
+    /// condition
+    async fn resolve_verified<C>(
+        &self,
+        env_id: Uuid,
+        input_schema: &[UiSchemaInput],
+        inputs: &serde_json::Value,
+        hidden: &BTreeSet<&str>,
+    ) -> Result<serde_json::Value, UiSchemaInputError<C::Error>>
     where
         C: UiSchemaCollections,
     {
         match self {
-            Self::FieldValue(fv) => Self::get_input::<C>(
-                Self::get_input_schema::<C>(input_schema, &fv.input)?,
-                inputs,
-                &fv.input,
-            ),
+            Self::FieldValue(fv) => {
+                let schema = Self::get_input_schema::<C>(input_schema, &fv.input)?;
+                Self::get_input::<C>(schema, inputs, hidden, &fv.input)
+            }
             Self::FieldProperty(fp) => {
                 let schema = Self::get_input_schema::<C>(input_schema, &fp.input)?;
-                match &schema.input_type.single_type {
-                    UiSchemaInputSingleType::CollectionSelect { collection } => {
-                        let collections: C = serde_json::from_value(collection.to_owned())
-                            .map_err(|err| {
-                                UiSchemaInputError::InvalidCollectionName(
-                                    collection.to_owned(),
-                                    err,
-                                )
-                            })?;
-                        let id_value = Self::get_input::<C>(schema, inputs, &fp.input)?;
-                        if schema.input_type.is_array {
-                            let id_value_arr = id_value.as_array().ok_or_else(|| {
-                                UiSchemaInputError::InputNotStringArray(fp.input.clone())
-                            })?;
-
-                            let mut resolved_arr = Vec::new();
-
-                            for id_value in id_value_arr {
-                                let id = id_value.as_str().ok_or_else(|| {
-                                    UiSchemaInputError::InputNotStringArray(fp.input.clone())
-                                })?;
-                                let resolved_value =
-                                    collections.resolve(env_id, id, &fp.property).await?;
-                                resolved_arr.push(resolved_value);
-                            }
-                            Ok(serde_json::to_value(resolved_arr).unwrap())
-                        } else {
-                            let id = id_value.as_str().ok_or_else(|| {
-                                UiSchemaInputError::InputNotString(fp.input.clone())
-                            })?;
-                            collections.resolve(env_id, id, &fp.property).await
-                        }
+                let collections = Self::get_collection::<C>(schema)?
+                    .ok_or_else(|| UiSchemaInputError::InputNotACollection(fp.input.clone()))?;
+                let id_value = Self::get_input::<C>(schema, inputs, hidden, &fp.input)?;
+                let ids = Self::get_ids::<C>(schema, &id_value)?;
+                if schema.input_type.is_array {
+                    let mut resolved_arr = Vec::new();
+                    for id in ids {
+                        let resolved_value = collections.resolve(env_id, id, &fp.property).await?;
+                        resolved_arr.push(resolved_value);
                     }
-                    _ => Err(UiSchemaInputError::InputNotACollection(fp.input.clone())),
+                    Ok(serde_json::to_value(resolved_arr).unwrap())
+                } else {
+                    collections.resolve(env_id, ids[0], &fp.property).await
                 }
             }
         }
     }
 }
 
+fn parse_collection<C>(collection: &serde_json::Value) -> Result<C, serde_json::Error>
+where
+    C: UiSchemaCollections,
+{
+    serde_json::from_value(collection.to_owned())
+}
+
 type Map = serde_json::Map<String, serde_json::Value>;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -454,7 +1539,19 @@ impl UiSchemaOutputValue {
     where
         C: UiSchemaCollections,
     {
-        match self.value.resolve::<C>(env_id, input_schema, inputs).await {
+        let resolved = self.value.resolve::<C>(env_id, input_schema, inputs).await;
+        self.insert_resolved(resolved, outputs)
+    }
+
+    fn insert_resolved<E>(
+        &self,
+        resolved: Result<serde_json::Value, UiSchemaInputError<E>>,
+        outputs: &mut Map,
+    ) -> Result<(), UiSchemaInputError<E>>
+    where
+        E: std::fmt::Display,
+    {
+        match resolved {
             Ok(value) => {
                 insert_into_map_ex(outputs, &self.path, value);
                 Ok(())
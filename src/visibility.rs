@@ -0,0 +1,318 @@
+use crate::error::UiSchemaVisibilityIssue;
+use crate::ui_schema::{UiSchema, UiSchemaInput, UiSchemaLayout, UiSchemaSection};
+use std::collections::{BTreeSet, HashMap};
+
+impl UiSchemaInput {
+    /// IDs of the inputs referenced by this input's `showIf` or
+    /// `showIfAll` condition, in order of first appearance.
+    pub fn visibility_dependencies(&self) -> Vec<String> {
+        let mut vars = Vec::new();
+        if let Some(show_if) = self.show_if() {
+            collect_vars(show_if, &mut vars);
+        } else if let Some(show_if_all) = self.show_if_all() {
+            vars.extend(show_if_all.iter().map(|fv| fv.field.clone()));
+        }
+        let mut seen = BTreeSet::new();
+        vars.retain(|var| seen.insert(var.clone()));
+        vars
+    }
+}
+
+impl UiSchemaSection {
+    /// IDs of the inputs referenced by this section's `showIf`
+    /// condition, in order of first appearance.
+    pub fn visibility_dependencies(&self) -> Vec<String> {
+        let mut vars = Vec::new();
+        if let Some(show_if) = self.show_if.as_ref() {
+            collect_vars(show_if, &mut vars);
+        }
+        let mut seen = BTreeSet::new();
+        vars.retain(|var| seen.insert(var.clone()));
+        vars
+    }
+}
+
+/// Collect the input IDs referenced using JsonLogic's `var`, `missing`
+/// and `missing_some` operators. Dotted paths refer to the input named by
+/// the first path element. Only the first operand of operators iterating
+/// over an array is evaluated against the inputs, the others refer to
+/// the current item, so they're skipped.
+fn collect_vars(logic: &serde_json::Value, vars: &mut Vec<String>) {
+    match logic {
+        serde_json::Value::Object(map) => {
+            for (op, args) in map.iter() {
+                match op.as_str() {
+                    "var" => match args {
+                        serde_json::Value::Array(args) => {
+                            if let Some(name) = args.first() {
+                                collect_var_name(name, vars);
+                            }
+                            for default in args.iter().skip(1) {
+                                collect_vars(default, vars);
+                            }
+                        }
+                        name => collect_var_name(name, vars),
+                    },
+                    "missing" => match args {
+                        serde_json::Value::Array(names) => {
+                            for name in names {
+                                collect_var_name(name, vars);
+                            }
+                        }
+                        name => collect_var_name(name, vars),
+                    },
+                    "missing_some" => {
+                        if let serde_json::Value::Array(args) = args {
+                            for arg in args.iter() {
+                                match arg {
+                                    serde_json::Value::Array(names) => {
+                                        for name in names {
+                                            collect_var_name(name, vars);
+                                        }
+                                    }
+                                    other => collect_vars(other, vars),
+                                }
+                            }
+                        }
+                    }
+                    "map" | "filter" | "reduce" | "all" | "some" | "none" => match args {
+                        serde_json::Value::Array(args) => {
+                            if let Some(array) = args.first() {
+                                collect_vars(array, vars);
+                            }
+                            // The initial accumulator of reduce
+                            if op == "reduce" {
+                                if let Some(initial) = args.get(2) {
+                                    collect_vars(initial, vars);
+                                }
+                            }
+                        }
+                        array => collect_vars(array, vars),
+                    },
+                    _ => collect_vars(args, vars),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_vars(item, vars);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// A variable name is either a literal path or logic computing it
+fn collect_var_name(name: &serde_json::Value, vars: &mut Vec<String>) {
+    match name {
+        serde_json::Value::String(name) => {
+            if let Some(id) = name.split('.').next().filter(|id| !id.is_empty()) {
+                vars.push(id.to_owned());
+            }
+        }
+        other => collect_vars(other, vars),
+    }
+}
+
+impl UiSchema {
+    /// Statically check all visibility conditions in the schema for
+    /// references to unknown inputs, dependency cycles and inputs that
+    /// can never be visible. An input in a layout section depends on the
+    /// inputs of the section's condition too.
+    pub fn analyze_visibility(&self) -> Vec<UiSchemaVisibilityIssue> {
+        analyze_visibility(self.get_inputs(), self.get_layout())
+    }
+}
+
+pub(crate) fn analyze_visibility(
+    inputs: &[UiSchemaInput],
+    layout: Option<&UiSchemaLayout>,
+) -> Vec<UiSchemaVisibilityIssue> {
+    let mut issues = Vec::new();
+    for section in layout.iter().flat_map(|layout| layout.sections.iter()) {
+        for variable in section.visibility_dependencies() {
+            if !inputs.iter().any(|input| input.id == variable) {
+                issues.push(UiSchemaVisibilityIssue::UnknownSectionVariable {
+                    section: section.id.clone(),
+                    variable,
+                });
+            }
+        }
+    }
+    let mut has_unknown = vec![false; inputs.len()];
+    let deps = dependency_graph(inputs, layout, |i, input, variable| {
+        has_unknown[i] = true;
+        issues.push(UiSchemaVisibilityIssue::UnknownVariable {
+            input: input.id.clone(),
+            variable,
+        })
+    });
+
+    let mut never_visible = vec![false; inputs.len()];
+    for cycle in find_cycles(&deps) {
+        for i in cycle.iter() {
+            never_visible[*i] = true;
+        }
+        issues.push(UiSchemaVisibilityIssue::DependencyCycle(
+            cycle.into_iter().map(|i| inputs[i].id.clone()).collect(),
+        ));
+    }
+
+    // Conditions not depending on any input are constant. Conditions
+    // referring to unknown variables are already reported.
+    for (i, input) in inputs.iter().enumerate() {
+        if deps[i].is_empty()
+            && !has_unknown[i]
+            && (input.show_if().is_some() || input.show_if_all().is_some())
+            && !never_visible[i]
+            && !input
+                .evaluate_visibility(&serde_json::Value::Object(Default::default()))
+                .unwrap_or(false)
+        {
+            never_visible[i] = true;
+            issues.push(UiSchemaVisibilityIssue::NeverVisible(input.id.clone()));
+        }
+    }
+
+    for (i, input) in inputs.iter().enumerate() {
+        for dep in deps[i]
+            .iter()
+            .filter(|dep| never_visible[**dep] && **dep != i)
+        {
+            if !(never_visible[i] && deps[*dep].contains(&i)) {
+                issues.push(UiSchemaVisibilityIssue::DependsOnNeverVisible {
+                    input: input.id.clone(),
+                    dependency: inputs[*dep].id.clone(),
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+/// Whether each input is part of a dependency cycle, including inputs
+/// depending on themselves. These inputs are never visible: a hidden
+/// input can't be filled in to make the inputs it depends on visible.
+pub(crate) fn cyclic_inputs(
+    inputs: &[UiSchemaInput],
+    layout: Option<&UiSchemaLayout>,
+) -> Vec<bool> {
+    let mut cyclic = vec![false; inputs.len()];
+    for i in find_cycles(&dependency_graph(inputs, layout, |_, _, _| ())).concat() {
+        cyclic[i] = true;
+    }
+    cyclic
+}
+
+/// For each input, the indices of the inputs its visibility depends on,
+/// including those its section's condition depends on. Variables of the
+/// input's own condition not naming an input are passed to `unknown`,
+/// along with the index of the input referring to them.
+fn dependency_graph(
+    inputs: &[UiSchemaInput],
+    layout: Option<&UiSchemaLayout>,
+    mut unknown: impl FnMut(usize, &UiSchemaInput, String),
+) -> Vec<Vec<usize>> {
+    let index: HashMap<&str, usize> = inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| (input.id.as_str(), i))
+        .collect();
+    inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| {
+            let mut deps: Vec<usize> = input
+                .visibility_dependencies()
+                .into_iter()
+                .filter_map(|var| match index.get(var.as_str()) {
+                    Some(dep) => Some(*dep),
+                    None => {
+                        unknown(i, input, var);
+                        None
+                    }
+                })
+                .collect();
+            let section = layout.and_then(|layout| layout.section_of(&input.id));
+            for var in section
+                .map(|s| s.visibility_dependencies())
+                .unwrap_or_default()
+            {
+                if let Some(dep) = index.get(var.as_str()) {
+                    if !deps.contains(dep) {
+                        deps.push(*dep);
+                    }
+                }
+            }
+            deps
+        })
+        .collect()
+}
+
+/// Find all strongly connected components forming cycles, including
+/// inputs depending on themselves (Tarjan's algorithm).
+fn find_cycles(deps: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    struct State<'a> {
+        deps: &'a [Vec<usize>],
+        index: Vec<Option<usize>>,
+        low_link: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        next_index: usize,
+        cycles: Vec<Vec<usize>>,
+    }
+
+    fn visit(state: &mut State, v: usize) {
+        state.index[v] = Some(state.next_index);
+        state.low_link[v] = state.next_index;
+        state.next_index += 1;
+        state.stack.push(v);
+        state.on_stack[v] = true;
+
+        for &w in state.deps[v].iter() {
+            match state.index[w] {
+                None => {
+                    visit(state, w);
+                    state.low_link[v] = state.low_link[v].min(state.low_link[w]);
+                }
+                Some(w_index) if state.on_stack[w] => {
+                    state.low_link[v] = state.low_link[v].min(w_index);
+                }
+                Some(_) => (),
+            }
+        }
+
+        if Some(state.low_link[v]) == state.index[v] {
+            let mut component = Vec::new();
+            while let Some(w) = state.stack.pop() {
+                state.on_stack[w] = false;
+                component.push(w);
+                if w == v {
+                    break;
+                }
+            }
+            if component.len() > 1 || state.deps[v].contains(&v) {
+                component.sort();
+                state.cycles.push(component);
+            }
+        }
+    }
+
+    let mut state = State {
+        deps,
+        index: vec![None; deps.len()],
+        low_link: vec![0; deps.len()],
+        on_stack: vec![false; deps.len()],
+        stack: Vec::new(),
+        next_index: 0,
+        cycles: Vec::new(),
+    };
+    for v in 0..deps.len() {
+        if state.index[v].is_none() {
+            visit(&mut state, v);
+        }
+    }
+    state.cycles.sort();
+    state.cycles
+}
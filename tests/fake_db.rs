@@ -1,8 +1,22 @@
-use platz_chart_ext::{UiSchemaCollections, UiSchemaInputError};
+use platz_chart_ext::{
+    UiSchemaCollectionProperty, UiSchemaCollectionPropertyType, UiSchemaCollections,
+    UiSchemaInputError, UiSchemaInputFieldValue,
+};
 use serde::{Deserialize, Serialize};
+use std::cell::Cell;
 use std::str::FromStr;
 use uuid::Uuid;
 
+thread_local! {
+    static LIST_CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Number of `list` calls made by the current thread
+#[allow(dead_code)]
+pub fn list_calls() -> usize {
+    LIST_CALLS.with(Cell::get)
+}
+
 #[derive(Debug, Deserialize, Serialize, strum::Display)]
 pub enum TestDb {
     First,
@@ -45,4 +59,43 @@ impl UiSchemaCollections for TestDb {
             _ => Err(UiSchemaInputError::UnsupportedCollection(self.to_string())),
         }
     }
+
+    fn properties(&self) -> Option<Vec<UiSchemaCollectionProperty>> {
+        let (name, property_type) = match self {
+            Self::First => ("a", UiSchemaCollectionPropertyType::String),
+            Self::Second => ("b", UiSchemaCollectionPropertyType::String),
+            Self::Third => return None,
+        };
+        Some(vec![
+            UiSchemaCollectionProperty::new("id", UiSchemaCollectionPropertyType::String),
+            UiSchemaCollectionProperty::new(name, property_type),
+        ])
+    }
+
+    async fn list(
+        &self,
+        _env_id: Uuid,
+        filters: &[UiSchemaInputFieldValue],
+    ) -> Result<Vec<String>, UiSchemaInputError<Self::Error>> {
+        LIST_CALLS.with(|calls| calls.set(calls.get() + 1));
+        match self {
+            Self::First => {
+                let mut ids: Vec<i64> = (1..=6).collect();
+                for filter in filters {
+                    match filter.field.as_str() {
+                        "parity" => ids
+                            .retain(|id| filter.value == if id % 2 == 0 { "even" } else { "odd" }),
+                        _ => {
+                            return Err(UiSchemaInputError::UnknownProperty(
+                                filter.field.to_owned(),
+                                self.to_string(),
+                            ))
+                        }
+                    }
+                }
+                Ok(ids.iter().map(ToString::to_string).collect())
+            }
+            _ => Err(UiSchemaInputError::UnsupportedCollection(self.to_string())),
+        }
+    }
 }
@@ -0,0 +1,173 @@
+// Shared by several test crates, each using only part of it
+#![allow(dead_code)]
+
+use platz_chart_ext::{
+    ChartExtActionResponseError, ChartExtActionTarget, ChartExtActionTargetResolver,
+    ChartExtActionTemplateError, ChartExtActionV0, ChartExtDeploymentContext,
+};
+use serde_json::json;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread::JoinHandle;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TestError {
+    #[error(transparent)]
+    Url(#[from] url::ParseError),
+    #[error(transparent)]
+    Request(#[from] reqwest::Error),
+    #[error(transparent)]
+    Response(#[from] ChartExtActionResponseError),
+    #[error(transparent)]
+    Template(#[from] ChartExtActionTemplateError),
+}
+
+/// An action calling `POST /api/v1/emergency-stop`, with fields of
+/// `extra` added or replaced
+pub fn action(extra: serde_json::Value) -> ChartExtActionV0 {
+    with_extra(
+        json!({
+            "id": "emergency_stop",
+            "allowed_role": "Owner",
+            "endpoint": "standard_ingress",
+            "path": "/api/v1/emergency-stop",
+            "method": "POST",
+            "title": "Emergency stop",
+            "description": "Stop all workers",
+        }),
+        extra,
+    )
+}
+
+/// A target calling `POST /api/v1/check`, with fields of `extra` added
+/// or replaced
+pub fn target(extra: serde_json::Value) -> ChartExtActionTarget {
+    with_extra(
+        json!({
+            "endpoint": "standard_ingress",
+            "path": "/api/v1/check",
+            "method": "POST",
+        }),
+        extra,
+    )
+}
+
+fn with_extra<T>(mut value: serde_json::Value, extra: serde_json::Value) -> T
+where
+    T: serde::de::DeserializeOwned,
+{
+    value
+        .as_object_mut()
+        .unwrap()
+        .extend(extra.as_object().unwrap().clone());
+    serde_json::from_value(value).unwrap()
+}
+
+/// Resolves targets relative to the test server's URL
+pub struct TestResolver(pub Url);
+
+impl ChartExtActionTargetResolver for TestResolver {
+    type Error = url::ParseError;
+
+    async fn resolve(&self, target: &ChartExtActionTarget) -> Result<Url, Self::Error> {
+        Ok(target.url_on(&self.0))
+    }
+}
+
+/// The deployment actions are called on
+pub fn deployment() -> ChartExtDeploymentContext {
+    ChartExtDeploymentContext {
+        id: Uuid::nil(),
+        name: "main-shop".to_owned(),
+        namespace: "shop".to_owned(),
+        env_id: Uuid::nil(),
+    }
+}
+
+pub struct TestResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl TestResponse {
+    pub fn ok(body: &str) -> Self {
+        Self {
+            status: 200,
+            headers: vec![("Content-Type".to_owned(), "application/json".to_owned())],
+            body: body.to_owned(),
+        }
+    }
+}
+
+/// Raw request as received by the server, with lowercase header names
+#[derive(Debug)]
+pub struct TestRequest {
+    pub request_line: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl TestRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Serve each response to a single connection, in order, and return
+/// the requests received once all responses were sent.
+pub fn serve(responses: Vec<TestResponse>) -> (Url, JoinHandle<Vec<TestRequest>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed binding test server");
+    let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+    let handle = std::thread::spawn(move || {
+        responses
+            .into_iter()
+            .map(|response| {
+                let (mut stream, _) = listener.accept().expect("Failed accepting connection");
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut headers = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    let (name, value) = line.split_once(':').unwrap();
+                    headers.push((name.trim().to_lowercase(), value.trim().to_owned()));
+                }
+                let content_length = headers
+                    .iter()
+                    .find(|(name, _)| name == "content-length")
+                    .map_or(0, |(_, value)| value.parse().unwrap());
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                let mut raw = format!("HTTP/1.1 {} Test\r\n", response.status);
+                for (name, value) in response.headers.iter() {
+                    raw.push_str(&format!("{name}: {value}\r\n"));
+                }
+                raw.push_str(&format!(
+                    "Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.body.len(),
+                    response.body
+                ));
+                stream.write_all(raw.as_bytes()).unwrap();
+
+                TestRequest {
+                    request_line: request_line.trim_end().to_owned(),
+                    headers,
+                    body: String::from_utf8(body).unwrap(),
+                }
+            })
+            .collect()
+    });
+    (url, handle)
+}
@@ -3,8 +3,9 @@ mod utils;
 
 use anyhow::Result;
 use fake_db::TestDb;
-use platz_chart_ext::UiSchema;
+use platz_chart_ext::{UiSchema, UiSchemaCollectionReference, UiSchemaValidationError};
 use serde_json::json;
+use std::collections::BTreeMap;
 use utils::load_chart;
 use uuid::Uuid;
 
@@ -63,3 +64,216 @@ async fn test_all_input_types() -> Result<()> {
     assert!(matches!(ui_schema, UiSchema::V0(_)));
     Ok(())
 }
+
+#[tokio::test]
+async fn test_collection_properties() -> Result<()> {
+    for chart in ["v0/chart1", "v0/chart2", "v0/chart5", "v1beta2/chart6"] {
+        load_chart(chart).await?.validate::<TestDb>()?;
+    }
+
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "a",
+                "type": "CollectionSelect",
+                "label": "Select A",
+                "collection": "First",
+            }
+        ],
+        "outputs": {
+            "values": [
+                {
+                    "path": ["config", "a"],
+                    "value": {
+                        "FieldProperty": {
+                            "input": "a",
+                            "property": "b",
+                        }
+                    }
+                }
+            ]
+        }
+    }))?;
+    assert!(matches!(
+        ui_schema.validate::<TestDb>(),
+        Err(UiSchemaValidationError::UnknownProperty(property, collection))
+            if property == "b" && collection == "First"
+    ));
+
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "a",
+                "type": "CollectionSelect",
+                "label": "Select A",
+                "collection": "Fourth",
+            }
+        ],
+    }))?;
+    assert!(matches!(
+        ui_schema.validate::<TestDb>(),
+        Err(UiSchemaValidationError::InvalidCollectionName(_, _))
+    ));
+
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [],
+        "outputs": {
+            "values": [],
+            "secrets": {
+                "secret": {
+                    "KEY": {
+                        "FieldValue": {
+                            "input": "missing",
+                        }
+                    }
+                }
+            }
+        }
+    }))?;
+    assert!(matches!(
+        ui_schema.validate::<TestDb>(),
+        Err(UiSchemaValidationError::MissingInputSchema(input)) if input == "missing"
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_collection_references() -> Result<()> {
+    let ui_schema = load_chart("v0/chart2")
+        .await?
+        .ui_schema
+        .expect("No ui_schema");
+    let inputs = json!({
+        "a": ["3", "4"],
+    });
+    assert_eq!(
+        ui_schema.find_collection_references(&inputs, &TestDb::First, "4")?,
+        vec![UiSchemaCollectionReference {
+            input: "a".to_owned(),
+            visible: true,
+            output_paths: vec![
+                vec!["config".to_owned(), "a".to_owned(), "id".to_owned()],
+                vec!["config".to_owned(), "a".to_owned(), "value".to_owned()],
+            ],
+            secrets: BTreeMap::new(),
+        }]
+    );
+    assert!(ui_schema.is_collection_in_inputs(&inputs, &TestDb::First, "3")?);
+    assert!(!ui_schema.is_collection_in_inputs(&inputs, &TestDb::First, "5")?);
+    assert!(!ui_schema.is_collection_in_inputs(&inputs, &TestDb::Second, "3")?);
+
+    let ui_schema = load_chart("v1beta2/chart6")
+        .await?
+        .ui_schema
+        .expect("No ui_schema");
+    let inputs = json!({
+        "required_bool": false,
+        "conditional_select": "123",
+    });
+    let references = ui_schema.find_collection_references(&inputs, &TestDb::First, "123")?;
+    assert_eq!(references.len(), 1);
+    assert_eq!(references[0].input, "conditional_select");
+    assert!(!references[0].visible);
+    assert_eq!(
+        references[0].secrets,
+        BTreeMap::from([("secret-env".to_owned(), vec!["SELECTED_SECRET".to_owned()])])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_collection_item_impact() -> Result<()> {
+    let ui_schema = load_chart("v1beta2/chart6")
+        .await?
+        .ui_schema
+        .expect("No ui_schema");
+    let inputs = json!({
+        "required_bool": true,
+        "conditional_select": "123",
+        "conditional_text": "condtext",
+    });
+
+    let impact = ui_schema.collection_item_impact(&inputs, &TestDb::First, "123", &["a"])?;
+    assert_eq!(
+        impact.output_paths,
+        vec![vec![
+            "config".to_owned(),
+            "selected".to_owned(),
+            "a".to_owned()
+        ]]
+    );
+    assert_eq!(
+        impact.secrets,
+        BTreeMap::from([("secret-env".to_owned(), vec!["SELECTED_SECRET".to_owned()])])
+    );
+
+    let impact = ui_schema.collection_item_impact(&inputs, &TestDb::First, "123", &["id"])?;
+    assert_eq!(impact.output_paths.len(), 1);
+    assert!(impact.secrets.is_empty());
+
+    assert!(ui_schema
+        .collection_item_impact(&inputs, &TestDb::First, "123", &["b"])?
+        .is_empty());
+    assert!(ui_schema
+        .collection_item_impact(&inputs, &TestDb::First, "124", &["a"])?
+        .is_empty());
+    assert!(ui_schema
+        .collection_item_impact(&inputs, &TestDb::Second, "123", &["a"])?
+        .is_empty());
+
+    let hidden_inputs = json!({
+        "required_bool": false,
+        "conditional_select": "123",
+    });
+    assert!(ui_schema
+        .collection_item_impact(&hidden_inputs, &TestDb::First, "123", &["a"])?
+        .is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_collection_chained_visibility() -> Result<()> {
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {"id": "enabled", "type": "Checkbox", "label": "Enabled"},
+            {
+                "id": "mode",
+                "type": "text",
+                "label": "Mode",
+                "showIf": {"===": [{"var": "enabled"}, true]},
+            },
+            {
+                "id": "a",
+                "type": "CollectionSelect",
+                "label": "Select A",
+                "collection": "First",
+                "showIfAll": [{"field": "mode", "value": "advanced"}],
+            },
+        ],
+        "outputs": {
+            "values": [
+                {"path": ["a"], "value": {"FieldProperty": {"input": "a", "property": "a"}}},
+            ],
+        },
+    }))?;
+
+    // a's own condition holds, but it depends on the hidden mode
+    let inputs = json!({"enabled": false, "mode": "advanced", "a": "3"});
+    let references = ui_schema.find_collection_references(&inputs, &TestDb::First, "3")?;
+    assert_eq!(references.len(), 1);
+    assert!(!references[0].visible);
+    assert!(ui_schema
+        .collection_item_impact(&inputs, &TestDb::First, "3", &["a"])?
+        .is_empty());
+
+    let inputs = json!({"enabled": true, "mode": "advanced", "a": "3"});
+    assert!(ui_schema.find_collection_references(&inputs, &TestDb::First, "3")?[0].visible);
+    assert!(!ui_schema
+        .collection_item_impact(&inputs, &TestDb::First, "3", &["a"])?
+        .is_empty());
+
+    Ok(())
+}
@@ -1,7 +1,294 @@
+mod fake_db;
+mod utils;
+
 use anyhow::Result;
+use fake_db::{list_calls, TestDb};
+use platz_chart_ext::{
+    UiSchema, UiSchemaCollectionItem, UiSchemaCollectionQuery, UiSchemaInputError,
+    UiSchemaValidationError,
+};
+use serde_json::json;
+use utils::load_chart;
+use uuid::Uuid;
 
 #[tokio::test]
 async fn test() -> Result<()> {
-    // TODO
+    let chart_ext = load_chart("v0/chart5").await?;
+    let ui_schema = chart_ext.ui_schema.expect("No ui_schema");
+    assert!(matches!(ui_schema, UiSchema::V0(_)));
+
+    let items = ui_schema
+        .list_collection_items::<TestDb>(Uuid::new_v4(), "a", &json!({"parity": "odd"}))
+        .await?;
+    assert_eq!(items, vec!["1", "3", "5"]);
+
+    let items = ui_schema
+        .list_collection_items::<TestDb>(Uuid::new_v4(), "even_a", &json!({}))
+        .await?;
+    assert_eq!(items, vec!["2", "4", "6"]);
+
+    let inputs = json!({
+        "parity": "odd",
+        "a": "3",
+        "even_a": ["2", "6"],
+    });
+    let values: serde_json::Value = ui_schema
+        .get_values::<TestDb>(Uuid::new_v4(), &inputs)
+        .await?
+        .into();
+    let expected = json!({
+        "config": {
+            "a": "a3",
+            "even_a": ["2", "6"],
+        }
+    });
+    assert_eq!(values, expected);
+
+    let inputs = json!({
+        "parity": "even",
+        "a": "3",
+    });
+    assert!(matches!(
+        ui_schema
+            .get_values::<TestDb>(Uuid::new_v4(), &inputs)
+            .await,
+        Err(UiSchemaInputError::CollectionItemFilteredOut(input, id)) if input == "a" && id == "3"
+    ));
+
+    let inputs = json!({
+        "parity": "odd",
+        "a": "3",
+        "even_a": ["2", "5"],
+    });
+    assert!(matches!(
+        ui_schema
+            .get_values::<TestDb>(Uuid::new_v4(), &inputs)
+            .await,
+        Err(UiSchemaInputError::CollectionItemFilteredOut(input, id)) if input == "even_a" && id == "5"
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search() -> Result<()> {
+    let chart_ext = load_chart("v0/chart5").await?;
+    let ui_schema = chart_ext.ui_schema.expect("No ui_schema");
+    let inputs = json!({"parity": "odd"});
+
+    let page = ui_schema
+        .search_collection_items::<TestDb>(
+            Uuid::new_v4(),
+            "a",
+            &inputs,
+            &UiSchemaCollectionQuery {
+                per_page: 2,
+                ..Default::default()
+            },
+        )
+        .await?;
+    assert_eq!(page.num_total, 3);
+    assert_eq!(
+        page.items,
+        vec![
+            UiSchemaCollectionItem {
+                id: "1".to_owned(),
+                name: "1".to_owned(),
+            },
+            UiSchemaCollectionItem {
+                id: "3".to_owned(),
+                name: "3".to_owned(),
+            },
+        ]
+    );
+
+    let page = ui_schema
+        .search_collection_items::<TestDb>(
+            Uuid::new_v4(),
+            "a",
+            &inputs,
+            &UiSchemaCollectionQuery {
+                page: 2,
+                per_page: 2,
+                ..Default::default()
+            },
+        )
+        .await?;
+    assert_eq!(page.num_total, 3);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].id, "5");
+
+    let page = ui_schema
+        .search_collection_items::<TestDb>(
+            Uuid::new_v4(),
+            "a",
+            &inputs,
+            &UiSchemaCollectionQuery {
+                search: Some("3".to_owned()),
+                ..Default::default()
+            },
+        )
+        .await?;
+    assert_eq!(page.num_total, 1);
+    assert_eq!(page.items[0].id, "3");
+
+    let page = ui_schema
+        .search_collection_items::<TestDb>(
+            Uuid::new_v4(),
+            "a",
+            &inputs,
+            &UiSchemaCollectionQuery {
+                page: u64::MAX,
+                per_page: u64::MAX,
+                ..Default::default()
+            },
+        )
+        .await?;
+    assert_eq!(page.per_page, UiSchemaCollectionQuery::MAX_PER_PAGE);
+    assert!(page.items.is_empty());
+
+    let query: UiSchemaCollectionQuery = serde_json::from_value(json!({"per_page": 10}))?;
+    assert_eq!(query.per_page, 10);
+    assert!(serde_json::from_value::<UiSchemaCollectionQuery>(json!({"per_page": 0})).is_err());
+    let query: UiSchemaCollectionQuery = serde_json::from_value(json!({"page": 2}))?;
+    assert_eq!(query.page, 2);
+    assert!(serde_json::from_value::<UiSchemaCollectionQuery>(json!({"page": 0})).is_err());
+
+    assert!(matches!(
+        ui_schema
+            .search_collection_items::<TestDb>(
+                Uuid::new_v4(),
+                "parity",
+                &inputs,
+                &Default::default(),
+            )
+            .await,
+        Err(UiSchemaInputError::InputNotACollection(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_missing_filter_input() -> Result<()> {
+    let chart_ext = load_chart("v0/chart5").await?;
+    let ui_schema = chart_ext.ui_schema.expect("No ui_schema");
+
+    // No items are offered until parity is picked
+    let inputs = json!({});
+    assert!(matches!(
+        ui_schema.get_inputs()[1].resolve_filters::<TestDb>(&inputs),
+        Err(UiSchemaInputError::FilterInputMissing(input, filter_input))
+            if input == "a" && filter_input == "parity"
+    ));
+    assert!(ui_schema
+        .list_collection_items::<TestDb>(Uuid::new_v4(), "a", &inputs)
+        .await?
+        .is_empty());
+    let page = ui_schema
+        .search_collection_items::<TestDb>(Uuid::new_v4(), "a", &inputs, &Default::default())
+        .await?;
+    assert_eq!(page.num_total, 0);
+    assert!(page.items.is_empty());
+
+    // So a selection made without it is filtered out
+    assert!(matches!(
+        ui_schema
+            .get_values::<TestDb>(Uuid::new_v4(), &json!({"a": "3"}))
+            .await,
+        Err(UiSchemaInputError::CollectionItemFilteredOut(input, id)) if input == "a" && id == "3"
+    ));
+
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "a",
+                "type": "CollectionSelect",
+                "label": "Select A",
+                "collection": "First",
+                "filters": [{"field": "parity", "input": "nope"}],
+            },
+        ],
+    }))?;
+    assert!(matches!(
+        ui_schema.validate::<TestDb>(),
+        Err(UiSchemaValidationError::UnknownFilterInput(input, filter_input))
+            if input == "a" && filter_input == "nope"
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_once_per_input() -> Result<()> {
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "a",
+                "type": "CollectionSelect",
+                "label": "Select A",
+                "collection": "First",
+                "filters": [{"field": "parity", "value": "odd"}],
+            },
+        ],
+        "outputs": {
+            "values": [
+                {"path": ["id"], "value": {"FieldValue": {"input": "a"}}},
+                {"path": ["a"], "value": {"FieldProperty": {"input": "a", "property": "a"}}},
+                {"path": ["id2"], "value": {"FieldProperty": {"input": "a", "property": "id"}}},
+            ],
+            "secrets": {
+                "config": {
+                    "a": {"FieldProperty": {"input": "a", "property": "a"}},
+                    "id": {"FieldValue": {"input": "a"}},
+                },
+            },
+        },
+    }))?;
+
+    let inputs = json!({"a": "3"});
+    let before = list_calls();
+    let values: serde_json::Value = ui_schema
+        .get_values::<TestDb>(Uuid::new_v4(), &inputs)
+        .await?
+        .into();
+    assert_eq!(values, json!({"id": "3", "a": "a3", "id2": "3"}));
+    assert_eq!(list_calls() - before, 1);
+
+    let before = list_calls();
+    let secrets = ui_schema
+        .get_secrets::<TestDb>(Uuid::new_v4(), &inputs)
+        .await?;
+    assert_eq!(secrets.len(), 1);
+    assert_eq!(list_calls() - before, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unsupported_list() -> Result<()> {
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "c",
+                "type": "CollectionSelect",
+                "label": "Select C",
+                "collection": "Third",
+                "filters": [{"field": "parity", "value": "even"}],
+            },
+        ],
+        "outputs": {
+            "values": [
+                {"path": ["c"], "value": {"FieldValue": {"input": "c"}}},
+            ],
+        },
+    }))?;
+
+    let values: serde_json::Value = ui_schema
+        .get_values::<TestDb>(Uuid::new_v4(), &json!({"c": "3"}))
+        .await?
+        .into();
+    assert_eq!(values, json!({"c": "3"}));
+
     Ok(())
 }
@@ -0,0 +1,111 @@
+use anyhow::Result;
+use platz_chart_ext::{UiSchema, UiSchemaInputRename, UiSchemaInputSingleType};
+use serde_json::json;
+
+#[test]
+fn test() -> Result<()> {
+    let old: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "num",
+                "type": "number",
+                "label": "Number",
+                "required": true,
+            },
+            {
+                "id": "name",
+                "type": "text",
+                "label": "Name",
+            },
+            {
+                "id": "flag",
+                "type": "Checkbox",
+                "label": "Flag",
+            },
+            {
+                "id": "removed",
+                "type": "text",
+                "label": "Removed",
+            },
+        ],
+    }))?;
+    let new: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "num",
+                "type": "text",
+                "label": "Number as text",
+                "required": true,
+            },
+            {
+                "id": "display_name",
+                "type": "text",
+                "label": "Display name",
+                "renamedFrom": ["name"],
+            },
+            {
+                "id": "flag",
+                "type": "Checkbox",
+                "label": "Flag",
+                "required": true,
+            },
+            {
+                "id": "with_default",
+                "type": "text",
+                "label": "With default",
+                "required": true,
+                "initialValue": "default",
+            },
+            {
+                "id": "added",
+                "type": "text",
+                "label": "Added",
+                "required": true,
+            },
+        ],
+    }))?;
+
+    // Inputs that were never renamed are serialized without renamedFrom
+    let serialized = serde_json::to_value(&new)?;
+    assert_eq!(serialized["inputs"][1]["renamedFrom"], json!(["name"]));
+    assert!(serialized["inputs"][0].get("renamedFrom").is_none());
+
+    let migration = new.migration_from(&old);
+    assert_eq!(migration.removed, vec!["removed"]);
+    assert_eq!(
+        migration.renamed,
+        vec![UiSchemaInputRename {
+            from: "name".to_owned(),
+            to: "display_name".to_owned(),
+        }]
+    );
+    assert_eq!(migration.retyped.len(), 1);
+    assert_eq!(migration.retyped[0].id, "num");
+    assert_eq!(
+        migration.retyped[0].old_type.single_type,
+        UiSchemaInputSingleType::Number
+    );
+    assert_eq!(
+        migration.retyped[0].new_type.single_type,
+        UiSchemaInputSingleType::Text
+    );
+    assert_eq!(migration.newly_required, vec!["flag", "added"]);
+
+    let inputs = json!({
+        "num": 5,
+        "name": "blah",
+        "removed": "still here",
+    });
+    assert_eq!(
+        migration.apply(&inputs),
+        json!({
+            "num": 5,
+            "display_name": "blah",
+            "removed": "still here",
+        })
+    );
+
+    assert!(new.migration_from(&new).is_empty());
+
+    Ok(())
+}
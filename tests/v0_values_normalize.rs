@@ -0,0 +1,131 @@
+mod utils;
+
+use anyhow::Result;
+use platz_chart_ext::UiSchema;
+use serde_json::json;
+use utils::load_chart;
+
+#[tokio::test]
+async fn test() -> Result<()> {
+    let chart_ext = load_chart("v0/chart4").await?;
+    let ui_schema = chart_ext.ui_schema.expect("No ui_schema");
+
+    let inputs = json!({
+        "required_enum": "value3",
+        "required_dependent_num": "5",
+        "ignored_field": 5,
+    });
+    assert_eq!(
+        ui_schema.normalize_inputs(&inputs)?,
+        json!({
+            "required_enum": "value3",
+            "required_dependent_num": 5,
+        })
+    );
+
+    // Switching required_enum back hides required_dependent_num
+    let inputs = json!({
+        "required_enum": "value2",
+        "required_dependent_num": 5,
+    });
+    assert_eq!(
+        ui_schema.normalize_inputs(&inputs)?,
+        json!({
+            "required_enum": "value2",
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_defaults() -> Result<()> {
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "enabled",
+                "type": "Checkbox",
+                "label": "Enabled",
+                "initialValue": true,
+            },
+            {
+                "id": "replicas",
+                "type": "number",
+                "label": "Replicas",
+                "initialValue": 1,
+                "showIf": {"var": "enabled"},
+            },
+            {
+                "id": "ports",
+                "type": "array",
+                "itemType": "number",
+                "label": "Ports",
+            },
+            {
+                "id": "name",
+                "type": "text",
+                "label": "Name",
+            },
+        ],
+    }))?;
+
+    assert_eq!(
+        ui_schema.normalize_inputs(&json!({
+            "ports": ["80", 443, "http"],
+            "name": "12",
+        }))?,
+        json!({
+            "enabled": true,
+            "replicas": 1,
+            "ports": [80, 443, "http"],
+            "name": "12",
+        })
+    );
+    assert_eq!(
+        ui_schema.normalize_inputs(&json!({
+            "enabled": false,
+            "replicas": 3,
+            "name": null,
+        }))?,
+        json!({
+            "enabled": false,
+        })
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hidden_section() -> Result<()> {
+    let chart_ext = load_chart("v1beta2/chart7").await?;
+    let ui_schema = chart_ext.ui_schema.expect("No ui_schema");
+
+    // debug_level is in the debug section, shown only when debugging
+    assert_eq!(
+        ui_schema.normalize_inputs(&json!({
+            "name": "blah",
+            "enable_debug": false,
+            "debug_level": "3",
+        }))?,
+        json!({
+            "name": "blah",
+            "replicas": 1,
+            "enable_debug": false,
+        })
+    );
+    assert_eq!(
+        ui_schema.normalize_inputs(&json!({
+            "name": "blah",
+            "enable_debug": true,
+            "debug_level": "3",
+        }))?,
+        json!({
+            "name": "blah",
+            "replicas": 1,
+            "enable_debug": true,
+            "debug_level": 3,
+        })
+    );
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+mod http_server;
+
+use anyhow::Result;
+use http_server::{serve, target, TestError, TestResolver, TestResponse};
+use platz_chart_ext::{ChartExtActionBodyEncoding, ChartExtActionClient};
+use serde_json::json;
+
+#[tokio::test]
+async fn test() -> Result<()> {
+    assert_eq!(
+        target(json!({"method": "GET"})).body_encoding(),
+        ChartExtActionBodyEncoding::Json
+    );
+    assert_eq!(
+        target(json!({"method": "DELETE"})).body_encoding(),
+        ChartExtActionBodyEncoding::Json
+    );
+    assert_eq!(
+        target(json!({"method": "GET", "body_encoding": "query"})).body_encoding(),
+        ChartExtActionBodyEncoding::Query
+    );
+    assert_eq!(
+        target(json!({"method": "PATCH"})).body_encoding(),
+        ChartExtActionBodyEncoding::Json
+    );
+
+    let (url, server) = serve(vec![
+        TestResponse::ok("[]"),
+        TestResponse::ok("[]"),
+        TestResponse::ok("[]"),
+        TestResponse::ok("[]"),
+    ]);
+    let client = ChartExtActionClient::default();
+    let resolver = TestResolver(url);
+    let body = json!({
+        "name": "my shop",
+        "limit": 10,
+        "tags": ["a", "b"],
+        "filter": {"region": "eu"},
+        "cursor": null,
+    });
+
+    for (method, body_encoding) in [
+        ("GET", Some("query")),
+        ("POST", Some("form")),
+        ("POST", None),
+        ("DELETE", Some("none")),
+    ] {
+        target(json!({
+            "path": "/api/v1/shops?active=true",
+            "method": method,
+            "body_encoding": body_encoding,
+        }))
+        .call_with::<_, _, TestError>(&client, &resolver, &body)
+        .await?;
+    }
+
+    let requests = server.join().unwrap();
+    assert_eq!(
+        requests[0].request_line,
+        "GET /api/v1/shops?active=true&filter=%7B%22region%22%3A%22eu%22%7D&limit=10&name=my+shop&tags=a&tags=b HTTP/1.1"
+    );
+    assert!(requests[0].body.is_empty());
+
+    assert_eq!(
+        requests[1].request_line,
+        "POST /api/v1/shops?active=true HTTP/1.1"
+    );
+    assert_eq!(
+        requests[1].header("content-type"),
+        Some("application/x-www-form-urlencoded")
+    );
+    assert_eq!(
+        requests[1].body,
+        "filter=%7B%22region%22%3A%22eu%22%7D&limit=10&name=my+shop&tags=a&tags=b"
+    );
+
+    assert_eq!(requests[2].header("content-type"), Some("application/json"));
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&requests[2].body)?,
+        body
+    );
+
+    assert_eq!(
+        requests[3].request_line,
+        "DELETE /api/v1/shops?active=true HTTP/1.1"
+    );
+    assert!(requests[3].body.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+mod http_server;
+
+use anyhow::Result;
+use http_server::{serve, target, TestError, TestResolver, TestResponse};
+use platz_chart_ext::ChartExtActionClient;
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde_json::json;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test() -> Result<()> {
+    let shared_client = ChartExtActionClient::new(reqwest::Client::new())
+        .timeout(Duration::from_secs(10))
+        .header(
+            HeaderName::from_static("x-platz-env"),
+            HeaderValue::from_static("production"),
+        );
+    let client = shared_client.clone().header(
+        HeaderName::from_static("x-platz-identity"),
+        HeaderValue::from_static("user-token"),
+    );
+
+    let (url, server) = serve(vec![
+        TestResponse {
+            status: 200,
+            headers: vec![("X-Request-Id".to_owned(), "abc".to_owned())],
+            body: "done".to_owned(),
+        },
+        TestResponse {
+            status: 404,
+            headers: vec![],
+            body: "no such shop".to_owned(),
+        },
+    ]);
+    let resolver = TestResolver(url);
+
+    let response = target(json!({"timeout_secs": 5}))
+        .call_with::<_, _, TestError>(&client, &resolver, json!({"all": true}))
+        .await?;
+    assert!(response.is_success());
+    assert_eq!(response.headers["x-request-id"], "abc");
+    assert_eq!(response.body, "done");
+
+    let response = target(json!({"timeout_secs": 5}))
+        .call_with::<_, _, TestError>(&shared_client, &resolver, json!({}))
+        .await?;
+    assert!(!response.is_success());
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+    assert_eq!(response.body, "no such shop");
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests[0].request_line, "POST /api/v1/check HTTP/1.1");
+    assert_eq!(requests[0].header("x-platz-env"), Some("production"));
+    assert_eq!(requests[0].header("x-platz-identity"), Some("user-token"));
+    assert_eq!(requests[0].body, r#"{"all":true}"#);
+    assert_eq!(requests[1].header("x-platz-env"), Some("production"));
+    assert_eq!(requests[1].header("x-platz-identity"), None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_call() -> Result<()> {
+    let (url, server) = serve(vec![TestResponse::ok("done")]);
+    let body = target(json!({"timeout_secs": 5}))
+        .call::<_, _, TestError>(&TestResolver(url), json!({}))
+        .await?;
+    assert_eq!(body, "done");
+    server.join().unwrap();
+    Ok(())
+}
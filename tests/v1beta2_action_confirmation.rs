@@ -0,0 +1,142 @@
+mod fake_db;
+mod http_server;
+
+use anyhow::Result;
+use fake_db::TestDb;
+use http_server::{action, deployment, serve, TestError, TestResolver, TestResponse};
+use platz_chart_ext::{
+    ChartExt, ChartExtActionClient, ChartExtActionPermission, ChartExtActionResponseError,
+    ChartExtActionResult, ChartExtActionTemplateError, ChartExtActionUserDeploymentRole,
+    ChartExtValidationError,
+};
+use serde_json::json;
+
+#[test]
+fn test_confirmation() -> Result<()> {
+    let mut by_name = action(json!({
+        "confirmation": {
+            "expected": "deployment_name",
+            "prompt": {"en": "Type the deployment name", "de": "Deployment-Namen eingeben"},
+        },
+    }));
+    assert_eq!(
+        by_name.is_allowed(None, ChartExtActionUserDeploymentRole::Owner),
+        ChartExtActionPermission::RequiresConfirmation
+    );
+    assert!(!by_name.is_confirmed(None, &deployment()));
+    assert!(!by_name.is_confirmed(Some("main"), &deployment()));
+    assert!(by_name.is_confirmed(Some("main-shop "), &deployment()));
+
+    by_name.localize("de");
+    let prompt = by_name.confirmation.unwrap().prompt.unwrap();
+    assert_eq!(prompt.get("en"), "Deployment-Namen eingeben");
+
+    let by_text = action(json!({"confirmation": {"expected": {"text": "STOP"}}}));
+    assert!(by_text.is_confirmed(Some("STOP"), &deployment()));
+    assert!(!by_text.is_confirmed(Some("main-shop"), &deployment()));
+
+    assert!(action(json!({})).is_confirmed(None, &deployment()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dry_run() -> Result<()> {
+    let (url, server) = serve(vec![
+        TestResponse::ok("Would stop 4 workers"),
+        TestResponse::ok("Would stop 4 workers"),
+    ]);
+    let client = ChartExtActionClient::default();
+    let resolver = TestResolver(url);
+
+    let by_header = action(json!({"dry_run": {"header": {"name": "X-Dry-Run"}}}));
+    let by_target = action(json!({
+        "dry_run": {
+            "target": {
+                "endpoint": "standard_ingress",
+                "path": "/api/v1/{deployment.namespace}/emergency-stop/preview",
+                "method": "POST",
+            },
+        },
+    }));
+    for action in [&by_header, &by_target] {
+        assert_eq!(
+            action
+                .dry_run_with::<_, _, TestError>(
+                    &client,
+                    &resolver,
+                    &deployment(),
+                    &json!({}),
+                    json!({})
+                )
+                .await?,
+            ChartExtActionResult::Text("Would stop 4 workers".to_owned())
+        );
+    }
+
+    let requests = server.join().unwrap();
+    assert_eq!(
+        requests[0].request_line,
+        "POST /api/v1/emergency-stop HTTP/1.1"
+    );
+    assert_eq!(requests[0].header("x-dry-run"), Some("true"));
+    assert_eq!(
+        requests[1].request_line,
+        "POST /api/v1/shop/emergency-stop/preview HTTP/1.1"
+    );
+    assert_eq!(requests[1].header("x-dry-run"), None);
+
+    assert!(matches!(
+        action(json!({}))
+            .dry_run_with::<_, _, TestError>(
+                &client,
+                &resolver,
+                &deployment(),
+                &json!({}),
+                json!({})
+            )
+            .await,
+        Err(TestError::Response(ChartExtActionResponseError::NoDryRun))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_validation() -> Result<()> {
+    let bad_path = action(json!({
+        "dry_run": {
+            "target": {
+                "endpoint": "standard_ingress",
+                "path": "/api/v1/{deployment.owner}/emergency-stop/preview",
+                "method": "POST",
+            },
+        },
+    }));
+    assert!(matches!(
+        bad_path.validate_path(),
+        Err(ChartExtActionTemplateError::UnknownVariable(name)) if name == "deployment.owner"
+    ));
+
+    let bad_header = action(json!({"dry_run": {"header": {"name": "X Dry Run"}}}));
+    let mut chart_ext = ChartExt::new_with_error(String::new());
+    chart_ext.actions = Some(serde_json::from_value(json!({
+        "schema_version": 0,
+        "actions": [serde_json::to_value(&bad_header)?],
+    }))?);
+    assert!(matches!(
+        chart_ext.validate::<TestDb>(),
+        Err(ChartExtValidationError::DryRun(
+            id,
+            ChartExtActionResponseError::InvalidDryRunHeader(name),
+        )) if id == "emergency_stop" && name == "X Dry Run"
+    ));
+
+    // Unset optional fields are omitted
+    let serialized = serde_json::to_value(action(json!({})))?;
+    for field in ["long_running", "confirmation", "dry_run"] {
+        assert!(serialized.get(field).is_none());
+    }
+
+    Ok(())
+}
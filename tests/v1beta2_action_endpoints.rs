@@ -0,0 +1,144 @@
+mod http_server;
+
+use anyhow::Result;
+use http_server::{deployment, TestError};
+use platz_chart_ext::{
+    ChartExtActionEndpoint, ChartExtActionEndpointScheme, ChartExtActionServiceEndpoint,
+    ChartExtActionTarget, ChartExtActionTargetResolver, ChartExtActionTemplateError,
+};
+use url::Url;
+/// Resolves endpoints the way a deployment in the `shop` namespace would
+struct NamespaceResolver;
+
+impl ChartExtActionTargetResolver for NamespaceResolver {
+    type Error = TestError;
+
+    async fn resolve(&self, target: &ChartExtActionTarget) -> Result<Url, Self::Error> {
+        let base = match &target.endpoint {
+            ChartExtActionEndpoint::StandardIngress => Url::parse("https://shop.example.com"),
+            ChartExtActionEndpoint::Service(service) => service.url("shop"),
+            ChartExtActionEndpoint::UrlTemplate(template) => {
+                return Ok(target.url_on(&deployment().render_url_template(template)?));
+            }
+            ChartExtActionEndpoint::IngressHost(name) => {
+                Url::parse(&format!("https://{name}.shop.example.com"))
+            }
+        }?;
+        Ok(target.url_on(&base))
+    }
+}
+
+/// Actions files are parsed through untagged enums, which accept maps
+/// for enum variants like JSON does.
+fn target(yaml: &str) -> ChartExtActionTarget {
+    let value: serde_json::Value = serde_yaml::from_str(yaml).unwrap();
+    serde_json::from_value(value).unwrap()
+}
+
+#[tokio::test]
+async fn test() -> Result<()> {
+    let standard = target("endpoint: standard_ingress\npath: /api/v1/check\nmethod: POST");
+    assert_eq!(standard.endpoint, ChartExtActionEndpoint::StandardIngress);
+    assert_eq!(
+        NamespaceResolver.resolve(&standard).await?.as_str(),
+        "https://shop.example.com/api/v1/check"
+    );
+
+    let service = target(
+        "
+endpoint:
+  service:
+    name: api
+    port: 8080
+path: /api/v1/check?all=true
+method: GET
+",
+    );
+    assert_eq!(
+        service.endpoint,
+        ChartExtActionEndpoint::Service(ChartExtActionServiceEndpoint {
+            name: "api".to_owned(),
+            port: 8080,
+            scheme: ChartExtActionEndpointScheme::Http,
+        })
+    );
+    assert_eq!(
+        NamespaceResolver.resolve(&service).await?.as_str(),
+        "http://api.shop.svc.cluster.local:8080/api/v1/check?all=true"
+    );
+
+    let url_template = target(
+        "
+endpoint:
+  url_template: https://admin.example.com/{deployment.namespace}/
+path: /api/v1/check
+method: POST
+",
+    );
+    assert_eq!(
+        NamespaceResolver.resolve(&url_template).await?.as_str(),
+        "https://admin.example.com/shop/api/v1/check"
+    );
+
+    let ingress_host = target(
+        "
+endpoint:
+  ingress_host: admin
+path: api/v1/check
+method: POST
+",
+    );
+    assert_eq!(
+        NamespaceResolver.resolve(&ingress_host).await?.as_str(),
+        "https://admin.shop.example.com/api/v1/check"
+    );
+
+    // Queries of the base URL are kept
+    let versioned = target(
+        "
+endpoint:
+  url_template: https://admin.example.com/{deployment.namespace}?api-version=2
+path: /api/v1/check?all=true
+method: GET
+",
+    );
+    assert_eq!(
+        NamespaceResolver.resolve(&versioned).await?.as_str(),
+        "https://admin.example.com/shop/api/v1/check?api-version=2&all=true"
+    );
+    let versioned = target(
+        "
+endpoint:
+  url_template: https://admin.example.com/{deployment.namespace}?api-version=2
+path: /api/v1/check
+method: GET
+",
+    );
+    assert_eq!(
+        NamespaceResolver.resolve(&versioned).await?.as_str(),
+        "https://admin.example.com/shop/api/v1/check?api-version=2"
+    );
+
+    // URL templates only have the deployment context
+    for template in [
+        "https://admin.example.com/{namespace}",
+        "https://admin.example.com/{shop_id}",
+    ] {
+        assert!(matches!(
+            deployment().render_url_template(template),
+            Err(ChartExtActionTemplateError::UnknownVariable(_))
+        ));
+    }
+    assert_eq!(
+        deployment()
+            .render_url_template("https://{deployment.name}.example.com")?
+            .as_str(),
+        "https://main-shop.example.com/"
+    );
+
+    let json = serde_json::to_value(&service)?;
+    let parsed: ChartExtActionTarget = serde_json::from_value(json)?;
+    assert_eq!(parsed.endpoint, service.endpoint);
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+mod http_server;
+
+use anyhow::Result;
+use http_server::{action, deployment, serve, TestError, TestResolver, TestResponse};
+use platz_chart_ext::{
+    ChartExtActionClient, ChartExtActionJobState, ChartExtActionResponseError,
+    ChartExtActionTemplateError, ChartExtActionV0,
+};
+use serde_json::json;
+use std::time::Duration;
+
+fn long_running(timeout_secs: Option<u64>) -> ChartExtActionV0 {
+    let mut long_running = json!({
+        "poll": {
+            "endpoint": "standard_ingress",
+            "path": "/api/v1/{deployment.namespace}/jobs/{job_id}",
+            "method": "GET",
+        },
+        "poll_interval_secs": 0,
+    });
+    if let Some(timeout_secs) = timeout_secs {
+        long_running["timeout_secs"] = timeout_secs.into();
+    }
+    action(json!({
+        "path": "/api/v1/{deployment.namespace}/emergency-stop",
+        "long_running": long_running,
+    }))
+}
+
+#[tokio::test]
+async fn test() -> Result<()> {
+    let (url, server) = serve(vec![
+        TestResponse::ok(r#"{"job_id": "job 1"}"#),
+        TestResponse::ok(r#"{"state": "Running", "progress": 50, "message": "Stopping"}"#),
+        TestResponse::ok(r#"{"state": "Succeeded", "progress": 100, "result": {"stopped": 4}}"#),
+    ]);
+    let client = ChartExtActionClient::default();
+    let resolver = TestResolver(url);
+
+    let action = long_running(None);
+    action.validate_path()?;
+    let long_running_settings = action.long_running.as_ref().unwrap();
+    assert_eq!(long_running_settings.timeout(), Duration::from_secs(3600));
+    assert_eq!(
+        long_running_settings.poll_interval(),
+        Duration::from_secs(1)
+    );
+
+    let poller = action
+        .start_job::<_, _, TestError>(&client, &resolver, &deployment(), &json!({}), json!({}))
+        .await?;
+    assert_eq!(poller.job.job_id, "job 1");
+
+    let mut progress = Vec::new();
+    let status = poller
+        .wait::<_, TestError, _>(&client, &resolver, |status| progress.push(status.progress))
+        .await?;
+    assert_eq!(status.state, ChartExtActionJobState::Succeeded);
+    assert_eq!(status.result, Some(json!({"stopped": 4})));
+    assert_eq!(progress, vec![Some(50), Some(100)]);
+
+    let requests = server.join().unwrap();
+    assert_eq!(
+        requests[0].request_line,
+        "POST /api/v1/shop/emergency-stop HTTP/1.1"
+    );
+    assert_eq!(
+        requests[1].request_line,
+        "GET /api/v1/shop/jobs/job%201 HTTP/1.1"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_errors() -> Result<()> {
+    let (url, server) = serve(vec![
+        TestResponse::ok(r#"{"job_id": "1"}"#),
+        TestResponse::ok(r#"{"state": "Pending"}"#),
+    ]);
+    let client = ChartExtActionClient::default();
+    let resolver = TestResolver(url);
+
+    let poller = long_running(Some(0))
+        .start_job::<_, _, TestError>(&client, &resolver, &deployment(), &json!({}), json!({}))
+        .await?;
+    let result = poller
+        .wait::<_, TestError, _>(&client, &resolver, |_| ())
+        .await;
+    assert!(matches!(
+        result,
+        Err(TestError::Response(ChartExtActionResponseError::JobTimeout(job_id))) if job_id == "1"
+    ));
+    server.join().unwrap();
+
+    let mut unknown_variable = long_running(None);
+    unknown_variable.long_running.as_mut().unwrap().poll.path = "/api/v1/jobs/{job}".to_owned();
+    assert!(matches!(
+        unknown_variable.validate_path(),
+        Err(ChartExtActionTemplateError::UnknownVariable(name)) if name == "job"
+    ));
+
+    let mut not_long_running = long_running(None);
+    not_long_running.long_running = None;
+    let result = not_long_running
+        .start_job::<_, _, TestError>(&client, &resolver, &deployment(), &json!({}), json!({}))
+        .await;
+    assert!(matches!(
+        result,
+        Err(TestError::Response(
+            ChartExtActionResponseError::NotLongRunning
+        ))
+    ));
+
+    Ok(())
+}
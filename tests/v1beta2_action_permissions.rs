@@ -0,0 +1,142 @@
+mod fake_db;
+mod http_server;
+mod utils;
+
+use anyhow::Result;
+use fake_db::TestDb;
+use http_server::action;
+use platz_chart_ext::resource_types::ChartExtResourceLifecycleV1Beta1;
+use platz_chart_ext::{
+    ChartExtActionDenialReason, ChartExtActionPermission, ChartExtActionUserDeploymentRole,
+    ChartExtActionV0, ChartExtFeatures, ChartExtValidationError,
+};
+use serde_json::json;
+use utils::load_chart;
+
+fn restart(allowed_role: &str, allowed_on_statuses: &[&str], dangerous: bool) -> ChartExtActionV0 {
+    action(json!({
+        "id": "restart",
+        "allowed_role": allowed_role,
+        "allowed_on_statuses": allowed_on_statuses,
+        "path": "/api/v1/restart",
+        "title": "Restart",
+        "description": "Restart all workers",
+        "dangerous": dangerous,
+    }))
+}
+
+#[test]
+fn test() -> Result<()> {
+    use ChartExtActionUserDeploymentRole::{EnvironmentAdmin, Maintainer, Owner, Viewer};
+
+    let any_status = restart("Maintainer", &[], false);
+    assert_eq!(
+        any_status.is_allowed(None, Maintainer),
+        ChartExtActionPermission::Allowed
+    );
+    assert_eq!(
+        any_status.is_allowed(Some("Running"), Owner),
+        ChartExtActionPermission::Allowed
+    );
+
+    assert_eq!(
+        any_status.is_allowed(None, Viewer),
+        ChartExtActionPermission::Denied(ChartExtActionDenialReason::Role)
+    );
+
+    let owner_only = restart("Owner", &[], false);
+    assert_eq!(
+        owner_only.is_allowed(None, EnvironmentAdmin),
+        ChartExtActionPermission::Allowed
+    );
+    assert_eq!(
+        owner_only.is_allowed(Some("Running"), Maintainer),
+        ChartExtActionPermission::Denied(ChartExtActionDenialReason::Role)
+    );
+
+    let running_only = restart("Maintainer", &["Running"], true);
+    assert_eq!(
+        running_only.is_allowed(Some("Running"), Maintainer),
+        ChartExtActionPermission::RequiresConfirmation
+    );
+    assert!(running_only
+        .is_allowed(Some("Running"), Maintainer)
+        .is_allowed());
+    assert_eq!(
+        running_only.is_allowed(Some("Stopped"), Maintainer),
+        ChartExtActionPermission::Denied(ChartExtActionDenialReason::Status)
+    );
+    assert_eq!(
+        running_only.is_allowed(None, Owner),
+        ChartExtActionPermission::Denied(ChartExtActionDenialReason::Status)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_roles() -> Result<()> {
+    use ChartExtActionUserDeploymentRole::{EnvironmentAdmin, Maintainer, Owner, Viewer};
+
+    assert!(Viewer.allows(Viewer));
+    assert!(Viewer.allows(EnvironmentAdmin));
+    assert!(Maintainer.allows(Owner));
+    assert!(!Owner.allows(Maintainer));
+    assert!(!EnvironmentAdmin.allows(Owner));
+    assert!(Owner < EnvironmentAdmin);
+
+    let lifecycle: ChartExtResourceLifecycleV1Beta1 = serde_json::from_value(json!({
+        "create": {},
+        "delete": {"allowed_role": "Owner"},
+    }))?;
+    let create = lifecycle.create.expect("No create action");
+    assert!(!create.is_allowed(Viewer));
+    assert!(create.is_allowed(Maintainer));
+    let delete = lifecycle.delete.expect("No delete action");
+    assert!(!delete.is_allowed(Maintainer));
+    assert!(delete.is_allowed(Owner));
+    assert!(delete.is_allowed(EnvironmentAdmin));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_validate_statuses() -> Result<()> {
+    let mut chart_ext = load_chart("v1beta2/chart3").await?;
+    // Only validate actions
+    chart_ext.ui_schema = None;
+    chart_ext.resource_types = None;
+    chart_ext.validate::<TestDb>()?;
+
+    let features = |statuses: &[&str]| -> ChartExtFeatures {
+        serde_json::from_value(json!({
+            "apiVersion": "platz.io/v1beta2",
+            "kind": "Features",
+            "spec": {
+                "status": {
+                    "endpoint": "standard_ingress",
+                    "path": "/api/v1/platz-status",
+                    "refresh_interval_secs": 15,
+                    "statuses": statuses,
+                },
+            },
+        }))
+        .unwrap()
+    };
+    let mut actions = serde_json::to_value(chart_ext.actions.as_ref().unwrap())?;
+    actions[0]["spec"]["allowed_on_statuses"] = json!(["Running", "Degarded"]);
+    chart_ext.actions = Some(serde_json::from_value(actions)?);
+
+    // Status names are only validated when declared
+    chart_ext.features = Some(features(&[]));
+    chart_ext.validate::<TestDb>()?;
+
+    chart_ext.features = Some(features(&["Running", "Degraded"]));
+    assert!(matches!(
+        chart_ext.validate::<TestDb>(),
+        Err(ChartExtValidationError::UnknownActionStatus(action, status))
+            if action == "check" && status == "Degarded"
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+mod http_server;
+
+use anyhow::Result;
+use http_server::{deployment, serve, TestError, TestResolver, TestResponse};
+use platz_chart_ext::{
+    ChartExtActionClient, ChartExtActionNotice, ChartExtActionNoticeSeverity,
+    ChartExtActionResponseError, ChartExtActionResponseFormat, ChartExtActionResult,
+    ChartExtActionV0, UiSchemaCollectionPropertyType,
+};
+use serde_json::json;
+
+fn format(value: serde_json::Value) -> ChartExtActionResponseFormat {
+    serde_yaml::from_str(&serde_yaml::to_string(&value).unwrap()).unwrap()
+}
+
+#[test]
+fn test_parse() -> Result<()> {
+    let text = ChartExtActionResponseFormat::default();
+    assert_eq!(
+        text.parse("Reindexed 3 shops")?,
+        ChartExtActionResult::Text("Reindexed 3 shops".to_owned())
+    );
+
+    let json = format(json!({
+        "type": "Json",
+        "schema": [
+            {"name": "reindexed", "type": "Number"},
+            {"name": "warnings", "type": "Array", "optional": true},
+        ],
+    }));
+    assert_eq!(
+        json.parse(r#"{"reindexed": 3}"#)?,
+        ChartExtActionResult::Json(json!({"reindexed": 3}))
+    );
+    assert!(matches!(
+        json.parse(r#"{"warnings": []}"#),
+        Err(ChartExtActionResponseError::MissingField(field)) if field == "reindexed"
+    ));
+    assert!(matches!(
+        json.parse(r#"{"reindexed": 3, "warnings": "none"}"#),
+        Err(ChartExtActionResponseError::InvalidFieldType(
+            field,
+            UiSchemaCollectionPropertyType::Array
+        )) if field == "warnings"
+    ));
+    assert!(matches!(
+        json.parse("[3]"),
+        Err(ChartExtActionResponseError::NotAnObject(_))
+    ));
+    assert!(matches!(
+        json.parse("Reindexed 3 shops"),
+        Err(ChartExtActionResponseError::InvalidJson(_))
+    ));
+
+    let notice = format(json!({"type": "Notice"}));
+    assert_eq!(
+        notice.parse(r#"{"severity": "Warning", "message": "2 shops were skipped"}"#)?,
+        ChartExtActionResult::Notice(ChartExtActionNotice {
+            severity: ChartExtActionNoticeSeverity::Warning,
+            message: "2 shops were skipped".to_owned(),
+        })
+    );
+    assert!(matches!(
+        notice.parse(r#"{"message": "Done"}"#),
+        Err(ChartExtActionResponseError::InvalidNotice(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_call() -> Result<()> {
+    let action: ChartExtActionV0 = serde_json::from_value(json!({
+        "id": "reindex",
+        "allowed_role": "Maintainer",
+        "endpoint": "standard_ingress",
+        "path": "/api/v1/reindex",
+        "method": "POST",
+        "title": "Reindex",
+        "description": "Reindex all shops",
+        "response": {"type": "Notice"},
+    }))?;
+    assert_eq!(
+        serde_json::to_value(&action)?["response"],
+        json!({"type": "Notice"})
+    );
+
+    // Actions without a response format are serialized without one
+    let mut text_action = action.clone();
+    text_action.response = None;
+    assert!(serde_json::to_value(&text_action)?
+        .get("response")
+        .is_none());
+
+    let (url, server) = serve(vec![
+        TestResponse::ok(r#"{"severity": "Success", "message": "Reindexed"}"#),
+        TestResponse {
+            status: 503,
+            headers: vec![],
+            body: "Unavailable".to_owned(),
+        },
+    ]);
+    let client = ChartExtActionClient::default();
+    let resolver = TestResolver(url);
+
+    let result = action
+        .call_with::<_, _, TestError>(&client, &resolver, &deployment(), &json!({}), json!({}))
+        .await?;
+    assert_eq!(
+        result,
+        ChartExtActionResult::Notice(ChartExtActionNotice {
+            severity: ChartExtActionNoticeSeverity::Success,
+            message: "Reindexed".to_owned(),
+        })
+    );
+
+    let result = action
+        .call_with::<_, _, TestError>(&client, &resolver, &deployment(), &json!({}), json!({}))
+        .await;
+    assert!(matches!(
+        result,
+        Err(TestError::Response(ChartExtActionResponseError::Status(status, body)))
+            if status == 503 && body == "Unavailable"
+    ));
+
+    server.join().unwrap();
+    Ok(())
+}
@@ -0,0 +1,87 @@
+mod http_server;
+
+use anyhow::Result;
+use http_server::{serve, target, TestError, TestResolver, TestResponse};
+use platz_chart_ext::{ChartExtActionClient, IDEMPOTENCY_KEY_HEADER};
+use reqwest::StatusCode;
+use serde_json::json;
+
+fn bad_gateway() -> TestResponse {
+    TestResponse {
+        status: 502,
+        headers: vec![],
+        body: "Bad gateway".to_owned(),
+    }
+}
+
+#[tokio::test]
+async fn test() -> Result<()> {
+    let (url, server) = serve(vec![bad_gateway(), bad_gateway(), TestResponse::ok("done")]);
+    let target = target(json!({"method": "PUT", "retry": {"initial_backoff_ms": 0}}));
+    let response = target
+        .call_with::<_, _, TestError>(
+            &ChartExtActionClient::default(),
+            &TestResolver(url),
+            json!({}),
+        )
+        .await?;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.body, "done");
+
+    let requests = server.join().unwrap();
+    let key = requests[0]
+        .header(&IDEMPOTENCY_KEY_HEADER.to_lowercase())
+        .expect("No idempotency key");
+    assert!(requests
+        .iter()
+        .all(|request| request.header(&IDEMPOTENCY_KEY_HEADER.to_lowercase()) == Some(key)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_attempts() -> Result<()> {
+    // Non-idempotent methods aren't retried by default
+    let (url, server) = serve(vec![bad_gateway()]);
+    let response = target(json!({"retry": {"initial_backoff_ms": 0}}))
+        .call_with::<_, _, TestError>(
+            &ChartExtActionClient::default(),
+            &TestResolver(url),
+            json!({}),
+        )
+        .await?;
+    assert_eq!(response.status, StatusCode::BAD_GATEWAY);
+    assert_eq!(server.join().unwrap().len(), 1);
+
+    let (url, server) = serve(vec![bad_gateway(), bad_gateway()]);
+    let response = target(json!({
+        "retry": {"max_attempts": 2, "initial_backoff_ms": 0, "retry_non_idempotent": true},
+    }))
+    .call_with::<_, _, TestError>(
+        &ChartExtActionClient::default(),
+        &TestResolver(url),
+        json!({}),
+    )
+    .await?;
+    assert_eq!(response.status, StatusCode::BAD_GATEWAY);
+    let requests = server.join().unwrap();
+    assert_ne!(
+        requests[0].header("idempotency-key"),
+        None,
+        "Idempotency key not sent"
+    );
+
+    // Status codes not listed aren't retried
+    let (url, server) = serve(vec![TestResponse {
+        status: 500,
+        headers: vec![],
+        body: "".to_owned(),
+    }]);
+    let result = target(json!({"method": "GET", "retry": {"initial_backoff_ms": 0}}))
+        .call::<_, _, TestError>(&TestResolver(url), json!({}))
+        .await;
+    assert!(result.is_err());
+    server.join().unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+mod http_server;
+
+use anyhow::Result;
+use http_server::{action, deployment};
+use platz_chart_ext::{ChartExtActionEndpoint, ChartExtActionTemplateError, ChartExtActionV0};
+use serde_json::json;
+
+fn reindex(path: &str) -> ChartExtActionV0 {
+    action(json!({
+        "id": "reindex",
+        "allowed_role": "Maintainer",
+        "path": path,
+        "title": "Reindex",
+        "description": "Reindex a shop",
+        "ui_schema": {
+            "inputs": [
+                {"id": "shop_id", "type": "text", "label": "Shop"},
+                {"id": "force", "type": "Checkbox", "label": "Force"},
+                {"id": "tags", "type": "array", "itemType": "text", "label": "Tags"},
+            ],
+            "outputs": {"values": []},
+        },
+    }))
+}
+
+#[test]
+fn test() -> Result<()> {
+    let action = reindex("/api/v1/shops/{shop_id}/reindex?force={force}&by={deployment.name}");
+    action.validate_path()?;
+    assert_eq!(
+        action.target.path_variables()?,
+        vec!["shop_id", "force", "deployment.name"]
+    );
+
+    let target =
+        action.render_target(&json!({"shop_id": "a/b?c d", "force": true}), &deployment())?;
+    assert_eq!(
+        target.path,
+        "/api/v1/shops/a%2Fb%3Fc%20d/reindex?force=true&by=main-shop"
+    );
+    // The original target is kept as a template
+    assert!(action.target.path.contains("{shop_id}"));
+
+    Ok(())
+}
+
+#[test]
+fn test_errors() -> Result<()> {
+    let action = reindex("/api/v1/shops/{shop_id}");
+    assert!(matches!(
+        action.render_target(&json!({}), &deployment()),
+        Err(ChartExtActionTemplateError::MissingValue(name)) if name == "shop_id"
+    ));
+    assert!(matches!(
+        action.render_target(&json!({"shop_id": ["a"]}), &deployment()),
+        Err(ChartExtActionTemplateError::InvalidValue(name, _)) if name == "shop_id"
+    ));
+
+    // Dot segments would escape the path, even when percent-encoded
+    for shop_id in [".", ".."] {
+        assert!(matches!(
+            action.render_target(&json!({"shop_id": shop_id}), &deployment()),
+            Err(ChartExtActionTemplateError::InvalidValue(name, _)) if name == "shop_id"
+        ));
+    }
+    let reindex_path = reindex("/api/v1/shops/{shop_id}/reindex");
+    assert!(matches!(
+        reindex_path.render_target(&json!({"shop_id": ".."}), &deployment()),
+        Err(ChartExtActionTemplateError::InvalidValue(name, _)) if name == "shop_id"
+    ));
+    let target = reindex_path.render_target(&json!({"shop_id": "..."}), &deployment())?;
+    assert_eq!(target.path, "/api/v1/shops/.../reindex");
+    let target = reindex("/api/v1/reindex?shop={shop_id}")
+        .render_target(&json!({"shop_id": ".."}), &deployment())?;
+    assert_eq!(target.path, "/api/v1/reindex?shop=..");
+
+    let unclosed = reindex("/api/v1/shops/{shop_id");
+    assert!(matches!(
+        unclosed.validate_path(),
+        Err(ChartExtActionTemplateError::Unclosed(_))
+    ));
+
+    // URL templates can't refer to inputs
+    let mut url_template = reindex("/api/v1/shops/{shop_id}");
+    url_template.target.endpoint =
+        ChartExtActionEndpoint::UrlTemplate("https://{shop_id}.example.com".to_owned());
+    assert!(matches!(
+        url_template.validate_path(),
+        Err(ChartExtActionTemplateError::UnknownVariable(name)) if name == "shop_id"
+    ));
+
+    for path in ["/api/v1/{deployment.owner}", "/api/v1/{shop}"] {
+        assert!(matches!(
+            reindex(path).validate_path(),
+            Err(ChartExtActionTemplateError::UnknownVariable(_))
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+mod fake_db;
+
+use anyhow::Result;
+use fake_db::TestDb;
+use platz_chart_ext::{
+    UiSchema, UiSchemaBuilder, UiSchemaInput, UiSchemaInputRef, UiSchemaInputSingleType,
+    UiSchemaInputType,
+};
+use rust_decimal::Decimal;
+use serde_json::json;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test() -> Result<()> {
+    let ui_schema = UiSchemaBuilder::new()
+        .input(
+            UiSchemaInput::builder(
+                "replicas",
+                UiSchemaInputType::single(UiSchemaInputSingleType::Number),
+                "Replicas",
+            )
+            .required(true)
+            .initial_value(json!(1))
+            .minimum(Decimal::ONE)
+            .maximum(Decimal::TEN)
+            .build(),
+        )
+        .input(
+            UiSchemaInput::builder(
+                "shops",
+                UiSchemaInputType::array(UiSchemaInputSingleType::CollectionSelect {
+                    collection: json!("First"),
+                }),
+                "Shops",
+            )
+            .help_text("Shops served by this deployment")
+            .show_if_all("replicas", json!(2))
+            .build(),
+        )
+        .output_value(&["replicas"], UiSchemaInputRef::field_value("replicas"))
+        .output_value(&["shops"], UiSchemaInputRef::field_property("shops", "a"))
+        .output_secret(
+            "shops",
+            "IDS",
+            UiSchemaInputRef::field_property("shops", "id"),
+        )
+        .build();
+    ui_schema.validate::<TestDb>()?;
+
+    let json = serde_json::to_value(&ui_schema)?;
+    assert_eq!(json["apiVersion"], "platz.io/v1beta1");
+    assert_eq!(json["kind"], "ValuesUi");
+    let parsed: UiSchema = serde_json::from_value(json)?;
+    assert!(matches!(parsed, UiSchema::V1Beta1(_)));
+
+    let replicas = &ui_schema.get_inputs()[0];
+    assert_eq!(replicas.label().get("en"), "Replicas");
+    assert_eq!(replicas.initial_value(), Some(&json!(1)));
+    assert_eq!(replicas.minimum(), Some(Decimal::ONE));
+    assert_eq!(replicas.maximum(), Some(Decimal::TEN));
+    assert!(replicas.step().is_none());
+    assert!(replicas.show_if().is_none());
+
+    let shops = &ui_schema.get_inputs()[1];
+    assert!(shops.help_text().is_some());
+    let show_if_all = shops.show_if_all().expect("No showIfAll");
+    assert_eq!(show_if_all[0].field, "replicas");
+    assert_eq!(show_if_all[0].value, json!(2));
+
+    let values: serde_json::Value = ui_schema
+        .get_values::<TestDb>(Uuid::new_v4(), &json!({"replicas": 2, "shops": ["1", "2"]}))
+        .await?
+        .into();
+    assert_eq!(values, json!({"replicas": 2, "shops": ["a1", "a2"]}));
+
+    Ok(())
+}
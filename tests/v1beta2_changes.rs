@@ -0,0 +1,306 @@
+mod utils;
+
+use anyhow::Result;
+use platz_chart_ext::resource_types::ChartExtResourceType;
+use platz_chart_ext::{
+    ChartExtActionUserDeploymentRole, ChartExtActions, ChartExtCardinality, ChartExtChange,
+    ChartExtSchemaScope, UiSchema,
+};
+use serde_json::json;
+use utils::load_chart;
+
+#[tokio::test]
+async fn test() -> Result<()> {
+    let old = load_chart("v1beta2/chart1").await?;
+    let mut new = load_chart("v1beta2/chart3").await?;
+
+    let changes = new.changes_from(&old);
+    assert_eq!(
+        changes,
+        vec![
+            ChartExtChange::CardinalityChanged {
+                from: ChartExtCardinality::OnePerCluster,
+                to: ChartExtCardinality::Many,
+            },
+            ChartExtChange::IngressDisabled,
+            ChartExtChange::StatusEnabled,
+        ]
+    );
+    assert!(!changes[0].is_breaking());
+    assert!(changes[1].is_breaking());
+    assert!(!changes[2].is_breaking());
+
+    let reverse = old.changes_from(&new);
+    assert!(reverse[0].is_breaking());
+    assert_eq!(reverse[1], ChartExtChange::IngressEnabled);
+
+    let mut ui_schema_json = serde_json::to_value(new.ui_schema.as_ref().unwrap())?;
+    let inputs = ui_schema_json["inputs"].as_array_mut().unwrap();
+    inputs.retain(|input| input["id"] != "optional_text");
+    inputs[0]["type"] = json!("text");
+    inputs.push(json!({
+        "id": "renamed_text",
+        "type": "text",
+        "label": "Renamed text",
+        "renamedFrom": ["required_text"],
+    }));
+    inputs.retain(|input| input["id"] != "required_text");
+    new.ui_schema = Some(serde_json::from_value::<UiSchema>(ui_schema_json)?);
+
+    let changes = new.changes_from(&old);
+    assert_eq!(
+        changes[..4],
+        [
+            ChartExtChange::InputRemoved {
+                scope: ChartExtSchemaScope::ValuesUi,
+                input: "optional_text".to_owned(),
+            },
+            ChartExtChange::InputRenamed {
+                scope: ChartExtSchemaScope::ValuesUi,
+                from: "required_text".to_owned(),
+                to: "renamed_text".to_owned(),
+            },
+            ChartExtChange::InputRetyped {
+                scope: ChartExtSchemaScope::ValuesUi,
+                input: "required_num".to_owned(),
+            },
+            ChartExtChange::CardinalityChanged {
+                from: ChartExtCardinality::OnePerCluster,
+                to: ChartExtCardinality::Many,
+            },
+        ]
+    );
+
+    assert!(old.changes_from(&old).is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_actions() -> Result<()> {
+    let old = load_chart("v1beta2/chart1").await?;
+    let mut new = load_chart("v1beta2/chart1").await?;
+    let Some(ChartExtActions::V1Beta1(actions)) = new.actions.as_mut() else {
+        panic!("Expected v1beta1 actions");
+    };
+    let mut actions_json = serde_json::to_value(&*actions)?;
+    let actions_arr = actions_json.as_array_mut().unwrap();
+    actions_arr.remove(1);
+    actions_arr[0]["spec"]["ui_schema"]["inputs"] = json!([
+        {
+            "id": "reason",
+            "type": "text",
+            "label": "Reason",
+            "required": true,
+        }
+    ]);
+    *actions = serde_json::from_value(actions_json)?;
+
+    let scope = ChartExtSchemaScope::Action("check".to_owned());
+    let changes = new.changes_from(&old);
+    assert_eq!(
+        changes,
+        vec![
+            ChartExtChange::InputAdded {
+                scope: scope.clone(),
+                input: "reason".to_owned(),
+            },
+            ChartExtChange::InputNowRequired {
+                scope,
+                input: "reason".to_owned(),
+            },
+            ChartExtChange::ActionRemoved("check2".to_owned()),
+        ]
+    );
+    assert_eq!(
+        changes.iter().filter(|change| change.is_breaking()).count(),
+        2
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_action_targets() -> Result<()> {
+    let mut old = load_chart("v1beta2/chart1").await?;
+    let mut new = load_chart("v1beta2/chart1").await?;
+    for chart_ext in [&mut old, &mut new] {
+        let Some(ChartExtActions::V1Beta1(actions)) = chart_ext.actions.as_mut() else {
+            panic!("Expected v1beta1 actions");
+        };
+        let mut actions_json = serde_json::to_value(&*actions)?;
+        actions_json[0]["spec"]["ui_schema"]["inputs"] = json!([
+            {"id": "reason", "type": "text", "label": "Reason"}
+        ]);
+        *actions = serde_json::from_value(actions_json)?;
+    }
+    let Some(ChartExtActions::V1Beta1(actions)) = new.actions.as_mut() else {
+        panic!("Expected v1beta1 actions");
+    };
+    let mut actions_json = serde_json::to_value(&*actions)?;
+    actions_json[0]["spec"]["ui_schema"]["inputs"] = json!([
+        {"id": "why", "type": "text", "label": "Why", "renamedFrom": ["reason"]}
+    ]);
+    actions_json[0]["spec"]["path"] = json!("/api/v2/check");
+    actions_json[0]["spec"]["allowed_role"] = json!("Owner");
+    actions_json[1]["spec"]["allowed_role"] = json!("Viewer");
+    *actions = serde_json::from_value(actions_json)?;
+
+    let changes = new.changes_from(&old);
+    assert_eq!(
+        changes,
+        vec![
+            ChartExtChange::ActionTargetChanged("check".to_owned()),
+            ChartExtChange::ActionRoleChanged {
+                action: "check".to_owned(),
+                from: ChartExtActionUserDeploymentRole::Maintainer,
+                to: ChartExtActionUserDeploymentRole::Owner,
+            },
+            ChartExtChange::InputRenamed {
+                scope: ChartExtSchemaScope::Action("check".to_owned()),
+                from: "reason".to_owned(),
+                to: "why".to_owned(),
+            },
+            ChartExtChange::ActionRoleChanged {
+                action: "check2".to_owned(),
+                from: ChartExtActionUserDeploymentRole::Maintainer,
+                to: ChartExtActionUserDeploymentRole::Viewer,
+            },
+        ]
+    );
+    assert_eq!(
+        changes
+            .iter()
+            .map(|change| change.is_breaking())
+            .collect::<Vec<_>>(),
+        vec![true, true, true, false]
+    );
+
+    // Renames are migrated in the values UI, so they're not breaking
+    assert!(!ChartExtChange::InputRenamed {
+        scope: ChartExtSchemaScope::ValuesUi,
+        from: "reason".to_owned(),
+        to: "why".to_owned(),
+    }
+    .is_breaking());
+
+    let with_status = load_chart("v1beta2/chart3").await?;
+    let mut without_status = load_chart("v1beta2/chart3").await?;
+    without_status.features = None;
+    let changes = without_status.changes_from(&with_status);
+    assert!(changes.contains(&ChartExtChange::StatusDisabled));
+    assert!(ChartExtChange::StatusDisabled.is_breaking());
+    assert!(with_status
+        .changes_from(&without_status)
+        .contains(&ChartExtChange::StatusEnabled));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_action_behavior() -> Result<()> {
+    let mut old = load_chart("v1beta2/chart1").await?;
+    let mut new = load_chart("v1beta2/chart1").await?;
+    let Some(ChartExtActions::V1Beta1(actions)) = old.actions.as_mut() else {
+        panic!("Expected v1beta1 actions");
+    };
+    let mut actions_json = serde_json::to_value(&*actions)?;
+    actions_json[1]["spec"]["allowed_on_statuses"] = json!(["Running", "Degraded"]);
+    *actions = serde_json::from_value(actions_json)?;
+
+    let Some(ChartExtActions::V1Beta1(actions)) = new.actions.as_mut() else {
+        panic!("Expected v1beta1 actions");
+    };
+    let mut actions_json = serde_json::to_value(&*actions)?;
+    actions_json[0]["spec"]["body_encoding"] = json!("query");
+    actions_json[0]["spec"]["long_running"] = json!({
+        "poll": {
+            "endpoint": "standard_ingress",
+            "path": "/api/v1/jobs/{job_id}",
+            "method": "GET",
+        },
+    });
+    actions_json[0]["spec"]["response"] = json!({"type": "Notice"});
+    actions_json[1]["spec"]["allowed_on_statuses"] = json!(["Running"]);
+    *actions = serde_json::from_value(actions_json)?;
+
+    let changes = new.changes_from(&old);
+    assert_eq!(
+        changes,
+        vec![
+            ChartExtChange::ActionTargetChanged("check".to_owned()),
+            ChartExtChange::ActionLongRunningChanged {
+                action: "check".to_owned(),
+                long_running: true,
+            },
+            ChartExtChange::ActionResponseChanged("check".to_owned()),
+            ChartExtChange::ActionStatusesChanged {
+                action: "check2".to_owned(),
+                from: vec!["Degraded".to_owned(), "Running".to_owned()],
+                to: vec!["Running".to_owned()],
+            },
+        ]
+    );
+    assert!(changes.iter().all(|change| change.is_breaking()));
+
+    // Allowing an action on more statuses is not breaking
+    let reverse = old.changes_from(&new);
+    assert_eq!(
+        reverse[3],
+        ChartExtChange::ActionStatusesChanged {
+            action: "check2".to_owned(),
+            from: vec!["Running".to_owned()],
+            to: vec!["Degraded".to_owned(), "Running".to_owned()],
+        }
+    );
+    assert!(!reverse[3].is_breaking());
+    assert!(ChartExtChange::ActionStatusesChanged {
+        action: "check2".to_owned(),
+        from: vec![],
+        to: vec!["Running".to_owned()],
+    }
+    .is_breaking());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_resource_types() -> Result<()> {
+    let old = load_chart("v1beta2/chart1").await?;
+    let mut new = load_chart("v1beta2/chart1").await?;
+    let ChartExtResourceType::V1Beta1(resource_type) =
+        &mut new.resource_types.as_mut().unwrap().0[0];
+    resource_type.spec.global = true;
+    let lifecycle = &mut resource_type.spec.lifecycle;
+    lifecycle.create.as_mut().unwrap().target = Some(serde_json::from_value(json!({
+        "endpoint": "standard_ingress",
+        "path": "/api/v1/shops",
+        "method": "POST",
+    }))?);
+    lifecycle.delete.as_mut().unwrap().allowed_role = Some(ChartExtActionUserDeploymentRole::Owner);
+    lifecycle.update = None;
+
+    let changes = new.changes_from(&old);
+    assert_eq!(
+        changes,
+        vec![
+            ChartExtChange::ResourceTypeGlobalChanged {
+                resource_type: "shop".to_owned(),
+                global: true,
+            },
+            ChartExtChange::LifecycleTargetChanged {
+                resource_type: "shop".to_owned(),
+                lifecycle_action: "create".to_owned(),
+            },
+            ChartExtChange::LifecycleRoleChanged {
+                resource_type: "shop".to_owned(),
+                lifecycle_action: "delete".to_owned(),
+                from: ChartExtActionUserDeploymentRole::Maintainer,
+                to: ChartExtActionUserDeploymentRole::Owner,
+            },
+        ]
+    );
+    assert!(changes.iter().all(|change| change.is_breaking()));
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+mod utils;
+
+use anyhow::Result;
+use platz_chart_ext::{ChartExtActionV0, LocalizedString};
+use serde_json::json;
+use utils::load_chart;
+
+/// Load the localized chart with all texts in `locale`, as JSON
+async fn localized(locale: &str) -> Result<serde_json::Value> {
+    let mut chart_ext = load_chart("v1beta2/chart8").await?;
+    assert!(chart_ext.error.is_none());
+    chart_ext.localize(locale);
+    Ok(json!({
+        "ui_schema": chart_ext.ui_schema,
+        "actions": chart_ext.actions,
+        "resource_types": chart_ext.resource_types,
+    }))
+}
+
+#[tokio::test]
+async fn test() -> Result<()> {
+    let de = localized("de").await?;
+    let inputs = &de["ui_schema"]["inputs"];
+    assert_eq!(inputs[0]["label"], "Modus");
+    assert_eq!(inputs[0]["helpText"], "Wie ausgeführt wird");
+    assert_eq!(inputs[0]["options"][0]["label"], "Schnell");
+    assert_eq!(inputs[0]["options"][0]["helpText"], "Überspringt Prüfungen");
+    let section = &de["ui_schema"]["layout"]["sections"][0];
+    assert_eq!(section["title"], "Allgemein");
+    assert_eq!(section["description"], "Grundeinstellungen");
+
+    let action = &de["actions"][0]["spec"];
+    assert_eq!(action["title"], "Neustarten");
+    assert_eq!(action["description"], "Alle Worker neu starten");
+    assert_eq!(
+        action["confirmation"]["prompt"],
+        "Deployment-Namen eingeben"
+    );
+    assert_eq!(action["ui_schema"]["inputs"][0]["label"], "Sanft");
+
+    let resource_type = &de["resource_types"][0]["spec"];
+    assert_eq!(resource_type["name_singular"], "Laden");
+    assert_eq!(resource_type["name_plural"], "Läden");
+    assert_eq!(resource_type["values_ui"]["inputs"][0]["label"], "Adresse");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fallback() -> Result<()> {
+    let label = |localized: &serde_json::Value, input: usize| {
+        localized["ui_schema"]["inputs"][input]["label"].clone()
+    };
+
+    // The exact locale
+    assert_eq!(label(&localized("de-AT").await?, 0), "Betriebsart");
+    let de_ch = localized("de-CH").await?;
+    assert_eq!(de_ch["actions"][0]["spec"]["title"], "Neu starten");
+
+    // Its language
+    assert_eq!(label(&de_ch, 0), "Modus");
+    assert_eq!(de_ch["resource_types"][0]["spec"]["name_singular"], "Laden");
+
+    // The default locale
+    let it = localized("it").await?;
+    assert_eq!(label(&it, 0), "Mode");
+    assert_eq!(it["actions"][0]["spec"]["title"], "Restart");
+    assert_eq!(it["resource_types"][0]["spec"]["name_plural"], "Shops");
+
+    // The first locale, without a default locale text
+    assert_eq!(label(&it, 1), "Nombre");
+    assert_eq!(label(&localized("fr").await?, 1), "Nom");
+
+    Ok(())
+}
+
+#[test]
+fn test_localized_action() -> Result<()> {
+    let mut action: ChartExtActionV0 = serde_json::from_value(json!({
+        "id": "stop",
+        "allowed_role": "Maintainer",
+        "endpoint": "standard_ingress",
+        "path": "/api/v1/stop",
+        "method": "POST",
+        "title": {
+            "en": "Stop",
+            "de": "Anhalten",
+        },
+        "description": "Stop the service",
+        "ui_schema": {
+            "inputs": [
+                {
+                    "id": "mode",
+                    "type": "RadioSelect",
+                    "label": {
+                        "en": "Mode",
+                        "de": "Modus",
+                    },
+                    "options": [
+                        {
+                            "value": "now",
+                            "label": {
+                                "en": "Now",
+                                "de": "Sofort",
+                            }
+                        }
+                    ]
+                }
+            ]
+        }
+    }))?;
+
+    assert_eq!(action.title.get("de"), "Anhalten");
+    assert_eq!(action.title.get("de-AT"), "Anhalten");
+    assert_eq!(action.title.get("fr"), "Stop");
+    assert_eq!(action.description.get("de"), "Stop the service");
+
+    let only_german = LocalizedString::Localized([("de".to_owned(), "Name".to_owned())].into());
+    assert_eq!(only_german.get("fr"), "Name");
+
+    action.localize("de");
+    let localized = serde_json::to_value(&action)?;
+    assert_eq!(localized["title"], "Anhalten");
+    assert_eq!(localized["description"], "Stop the service");
+    assert_eq!(localized["ui_schema"]["inputs"][0]["label"], "Modus");
+    assert_eq!(
+        localized["ui_schema"]["inputs"][0]["options"][0]["label"],
+        "Sofort"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+mod http_server;
+mod utils;
+
+use anyhow::Result;
+use http_server::{serve, TestResolver, TestResponse};
+use platz_chart_ext::{
+    ChartExtActionClient, ChartExtStatusError, PlatzStatus, PlatzStatusColor,
+    PlatzStatusNoticeLevel, STATUS_BODY_SNIPPET_LEN,
+};
+use rust_decimal::Decimal;
+use std::time::Duration;
+use utils::load_chart;
+
+#[tokio::test]
+async fn test() -> Result<()> {
+    let chart_ext = load_chart("v1beta2/chart3").await?;
+    let features = chart_ext.features.expect("No features");
+    let status_feature = features.status().expect("No status feature");
+    assert_eq!(status_feature.refresh_interval(), Duration::from_secs(15));
+    // Charts not declaring statuses are serialized without them
+    assert!(serde_json::to_value(status_feature)?
+        .get("statuses")
+        .is_none());
+
+    let (url, server) = serve(vec![TestResponse::ok(
+        r#"{
+            "status": {"name": "Running", "color": "Success"},
+            "primary_metric": {
+                "value": 12.5,
+                "unit": "rps",
+                "short_description": "Requests",
+                "color": null
+            },
+            "notices": [{"level": "Warning", "text": "Disk almost full"}]
+        }"#,
+    )]);
+    let status = status_feature
+        .fetch(&ChartExtActionClient::default(), &TestResolver(url))
+        .await?;
+    assert_eq!(status.status.name, "Running");
+    assert_eq!(status.status.color, PlatzStatusColor::Success);
+    let primary_metric = status.primary_metric.expect("No primary metric");
+    assert_eq!(primary_metric.value, Decimal::new(125, 1));
+    assert_eq!(primary_metric.unit, "rps");
+    assert!(status.metrics.is_empty());
+    assert_eq!(status.notices.len(), 1);
+    assert_eq!(status.notices[0].level, PlatzStatusNoticeLevel::Warning);
+
+    let requests = server.join().unwrap();
+    assert_eq!(
+        requests[0].request_line,
+        "GET /api/v1/platz-status HTTP/1.1"
+    );
+    assert!(requests[0].header("host").is_some());
+    assert!(requests[0].body.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_errors() -> Result<()> {
+    let chart_ext = load_chart("v1beta2/chart3").await?;
+    let features = chart_ext.features.expect("No features");
+    let status_feature = features.status().expect("No status feature");
+
+    let (url, server) = serve(vec![TestResponse::ok(r#"{"status": "Running"}"#)]);
+    let result = status_feature
+        .fetch(&ChartExtActionClient::default(), &TestResolver(url))
+        .await;
+    assert!(matches!(
+        result,
+        Err(ChartExtStatusError::Parse(_, body)) if body == r#"{"status": "Running"}"#
+    ));
+    server.join().unwrap();
+
+    let long_body = format!("<html>{}</html>", "é".repeat(STATUS_BODY_SNIPPET_LEN));
+    let (url, server) = serve(vec![TestResponse::ok(&long_body)]);
+    let result = status_feature
+        .fetch(&ChartExtActionClient::default(), &TestResolver(url))
+        .await;
+    assert!(matches!(
+        result,
+        Err(ChartExtStatusError::Parse(_, body))
+            if body.len() <= STATUS_BODY_SNIPPET_LEN && long_body.starts_with(&body)
+    ));
+    server.join().unwrap();
+
+    let (url, server) = serve(vec![TestResponse {
+        status: 500,
+        headers: vec![],
+        body: "".to_owned(),
+    }]);
+    let result: Result<PlatzStatus, _> = status_feature
+        .fetch(&ChartExtActionClient::default(), &TestResolver(url))
+        .await;
+    assert!(matches!(result, Err(ChartExtStatusError::Request(_))));
+    server.join().unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+mod fake_db;
+mod utils;
+
+use anyhow::Result;
+use fake_db::TestDb;
+use platz_chart_ext::{
+    diff_secrets, diff_values, UiSchema, UiSchemaChangeKind, UiSchemaInputRef,
+    UiSchemaSecretChange, UiSchemaValuesChange,
+};
+use serde_json::json;
+use utils::load_chart;
+use uuid::Uuid;
+
+fn path(parts: &[&str]) -> Vec<String> {
+    parts.iter().map(|s| s.to_string()).collect()
+}
+
+#[tokio::test]
+async fn test() -> Result<()> {
+    let chart_ext = load_chart("v1beta2/chart6").await?;
+    let ui_schema = chart_ext.ui_schema.expect("No ui_schema");
+    let env_id = Uuid::new_v4();
+
+    let old_inputs = json!({
+        "required_bool": true,
+        "conditional_select": "123",
+        "conditional_text": "condtext"
+    });
+    let new_inputs = json!({
+        "required_bool": true,
+        "conditional_select": "124",
+        "conditional_text": "condtext"
+    });
+    let old_values = ui_schema.get_values::<TestDb>(env_id, &old_inputs).await?;
+    let new_values = ui_schema.get_values::<TestDb>(env_id, &new_inputs).await?;
+
+    let changes = diff_values(&old_values, &new_values);
+    assert_eq!(
+        changes,
+        vec![
+            UiSchemaValuesChange {
+                path: path(&["config", "selected", "a"]),
+                kind: UiSchemaChangeKind::Changed,
+                old_value: Some(json!("a123")),
+                new_value: Some(json!("a124")),
+            },
+            UiSchemaValuesChange {
+                path: path(&["config", "selected", "id"]),
+                kind: UiSchemaChangeKind::Changed,
+                old_value: Some(json!("123")),
+                new_value: Some(json!("124")),
+            },
+        ]
+    );
+
+    let outputs = ui_schema.get_outputs_for_path(&changes[0].path);
+    assert_eq!(outputs.len(), 1);
+    assert!(matches!(
+        &outputs[0].value,
+        UiSchemaInputRef::FieldProperty(fp) if fp.input == "conditional_select" && fp.property == "a"
+    ));
+
+    let old_secrets = ui_schema.get_secrets::<TestDb>(env_id, &old_inputs).await?;
+    let new_secrets = ui_schema.get_secrets::<TestDb>(env_id, &new_inputs).await?;
+    assert_eq!(
+        diff_secrets(&old_secrets, &new_secrets),
+        vec![UiSchemaSecretChange {
+            name: "secret-env".to_owned(),
+            key: "SELECTED_SECRET".to_owned(),
+            kind: UiSchemaChangeKind::Changed,
+        }]
+    );
+
+    let hidden_inputs = json!({
+        "required_bool": false,
+    });
+    let hidden_values = ui_schema
+        .get_values::<TestDb>(env_id, &hidden_inputs)
+        .await?;
+    let changes = diff_values(&old_values, &hidden_values);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].path, path(&["config"]));
+    assert_eq!(changes[0].kind, UiSchemaChangeKind::Removed);
+    assert_eq!(ui_schema.get_outputs_for_path(&changes[0].path).len(), 2);
+
+    let hidden_secrets = ui_schema
+        .get_secrets::<TestDb>(env_id, &hidden_inputs)
+        .await?;
+    let changes = diff_secrets(&old_secrets, &hidden_secrets);
+    assert_eq!(changes.len(), 2);
+    assert!(changes
+        .iter()
+        .all(|change| change.kind == UiSchemaChangeKind::Removed));
+
+    Ok(())
+}
+
+#[test]
+fn test_arrays() {
+    let old = json!({"a": [1, 2], "b": {"c": 1}});
+    let new = json!({"a": [1, 3, 4], "b": {"c": 1, "d": 2}});
+    let changes = diff_values(old.as_object().unwrap(), new.as_object().unwrap());
+    assert_eq!(
+        changes
+            .iter()
+            .map(|change| (change.path.clone(), change.kind))
+            .collect::<Vec<_>>(),
+        vec![
+            (path(&["a", "[1]"]), UiSchemaChangeKind::Changed),
+            (path(&["a", "[2]"]), UiSchemaChangeKind::Added),
+            (path(&["b", "d"]), UiSchemaChangeKind::Added),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_sensitive() -> Result<()> {
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {"id": "user", "type": "text", "label": "User"},
+            {"id": "password", "type": "text", "label": "Password", "sensitive": true},
+        ],
+        "outputs": {
+            "values": [
+                {"path": ["db", "user"], "value": {"FieldValue": {"input": "user"}}},
+                {"path": ["db", "password"], "value": {"FieldValue": {"input": "password"}}},
+            ],
+        },
+    }))?;
+    let env_id = Uuid::new_v4();
+
+    let old_values = ui_schema
+        .get_values::<TestDb>(env_id, &json!({"user": "admin", "password": "hunter2"}))
+        .await?;
+    let new_values = ui_schema
+        .get_values::<TestDb>(env_id, &json!({"user": "root", "password": "hunter3"}))
+        .await?;
+    assert_eq!(
+        ui_schema.diff_values(&old_values, &new_values),
+        vec![
+            UiSchemaValuesChange {
+                path: path(&["db", "password"]),
+                kind: UiSchemaChangeKind::Changed,
+                old_value: None,
+                new_value: None,
+            },
+            UiSchemaValuesChange {
+                path: path(&["db", "user"]),
+                kind: UiSchemaChangeKind::Changed,
+                old_value: Some(json!("admin")),
+                new_value: Some(json!("root")),
+            },
+        ]
+    );
+
+    // Changes including a sensitive value are masked as a whole
+    let empty_values = ui_schema.get_values::<TestDb>(env_id, &json!({})).await?;
+    let changes = ui_schema.diff_values(&empty_values, &new_values);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].path, path(&["db"]));
+    assert_eq!(changes[0].kind, UiSchemaChangeKind::Added);
+    assert_eq!(changes[0].new_value, None);
+
+    Ok(())
+}
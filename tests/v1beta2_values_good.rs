@@ -8,6 +8,7 @@ use fake_db::TestDb;
 use platz_chart_ext::{
     ChartExtDeploymentDisplay, ChartExtDeploymentDisplayIcon, ChartExtDeploymentDisplayName,
     ChartExtDeploymentDisplayNameInputField, ChartExtIngressHostnameFormat, UiSchema,
+    UiSchemaLayoutMode, UiSchemaValidationError, UiSchemaVisibilityIssue,
 };
 use serde_json::json;
 use url::Url;
@@ -357,3 +358,127 @@ async fn test5() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test6() -> Result<()> {
+    let chart_ext = load_chart("v1beta2/chart7").await?;
+    chart_ext.validate::<TestDb>()?;
+
+    let ui_schema = chart_ext.ui_schema.expect("No ui_schema");
+    let layout = ui_schema.get_layout().expect("No layout");
+    assert_eq!(layout.mode, UiSchemaLayoutMode::Wizard);
+    assert_eq!(
+        layout
+            .sections
+            .iter()
+            .map(|section| section.id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["general", "advanced", "debug"]
+    );
+    assert!(!layout.sections[0].advanced);
+    assert!(layout.sections[1].advanced);
+    assert_eq!(layout.sections[1].inputs, vec!["replicas", "enable_debug"]);
+    assert!(layout.sections[2].show_if.is_some());
+    assert_eq!(ui_schema.get_inputs().len(), 4);
+
+    // Inputs of a hidden section are hidden
+    let inputs = json!({
+        "name": "blah",
+        "enable_debug": false,
+        "debug_level": 3,
+    });
+    let values: serde_json::Value = ui_schema
+        .get_values::<TestDb>(Uuid::new_v4(), &inputs)
+        .await?
+        .into();
+    assert_eq!(values, json!({"name": "blah"}));
+    assert_eq!(
+        ui_schema
+            .visible_inputs(&inputs)?
+            .iter()
+            .map(|input| input.id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["name", "replicas", "enable_debug"]
+    );
+
+    let inputs = json!({
+        "name": "blah",
+        "enable_debug": true,
+        "debug_level": 3,
+    });
+    let values: serde_json::Value = ui_schema
+        .get_values::<TestDb>(Uuid::new_v4(), &inputs)
+        .await?
+        .into();
+    let expected = json!({
+        "name": "blah",
+        "debug": {
+            "level": 3,
+        }
+    });
+    assert_eq!(values, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_layout_validation() -> Result<()> {
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [],
+        "layout": {
+            "sections": [
+                {
+                    "id": "general",
+                    "title": "General",
+                    "inputs": ["missing"],
+                }
+            ]
+        }
+    }))?;
+    assert!(matches!(
+        ui_schema.validate::<TestDb>(),
+        Err(UiSchemaValidationError::UnknownSectionInput(section, input))
+            if section == "general" && input == "missing"
+    ));
+
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [{"id": "name", "type": "text", "label": "Name"}],
+        "layout": {
+            "sections": [
+                {"id": "general", "title": "General", "inputs": ["name"]},
+                {"id": "other", "title": "Other", "inputs": ["name"]},
+            ]
+        }
+    }))?;
+    assert!(matches!(
+        ui_schema.validate::<TestDb>(),
+        Err(UiSchemaValidationError::DuplicateSectionInput(input, first, second))
+            if input == "name" && first == "general" && second == "other"
+    ));
+
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [{"id": "name", "type": "text", "label": "Name"}],
+        "layout": {
+            "sections": [
+                {
+                    "id": "general",
+                    "title": "General",
+                    "inputs": ["name"],
+                    "showIf": {"var": "missing"},
+                },
+            ]
+        }
+    }))?;
+    assert!(matches!(
+        ui_schema.validate::<TestDb>(),
+        Err(UiSchemaValidationError::Visibility(
+            UiSchemaVisibilityIssue::UnknownSectionVariable { section, variable }
+        )) if section == "general" && variable == "missing"
+    ));
+
+    // Schemas without a layout are serialized without one
+    let ui_schema: UiSchema = serde_json::from_value(json!({"inputs": []}))?;
+    assert!(serde_json::to_value(&ui_schema)?.get("layout").is_none());
+
+    Ok(())
+}
@@ -0,0 +1,451 @@
+mod fake_db;
+
+use anyhow::Result;
+use fake_db::TestDb;
+use platz_chart_ext::{
+    UiSchema, UiSchemaValidationError, UiSchemaVisibilityError, UiSchemaVisibilityIssue,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+fn visible_ids(ui_schema: &UiSchema, inputs: serde_json::Value) -> Result<Vec<String>> {
+    Ok(ui_schema
+        .visible_inputs(&inputs)?
+        .into_iter()
+        .map(|input| input.id.clone())
+        .collect())
+}
+
+#[test]
+fn test() -> Result<()> {
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "enabled",
+                "type": "Checkbox",
+                "label": "Enabled",
+            },
+            {
+                "id": "mode",
+                "type": "text",
+                "label": "Mode",
+                "showIf": {"===": [{"var": "enabled"}, true]},
+            },
+            {
+                "id": "level",
+                "type": "number",
+                "label": "Level",
+                "showIfAll": [
+                    {
+                        "field": "mode",
+                        "value": "advanced",
+                    }
+                ],
+            },
+            {
+                "id": "always",
+                "type": "text",
+                "label": "Always",
+            },
+        ],
+    }))?;
+
+    assert_eq!(
+        visible_ids(
+            &ui_schema,
+            json!({"enabled": true, "mode": "advanced", "level": 3})
+        )?,
+        vec!["enabled", "mode", "level", "always"]
+    );
+    assert_eq!(
+        visible_ids(
+            &ui_schema,
+            json!({"enabled": true, "mode": "simple", "level": 3})
+        )?,
+        vec!["enabled", "mode", "always"]
+    );
+    // mode is hidden, so level is hidden even though mode is "advanced"
+    assert_eq!(
+        visible_ids(
+            &ui_schema,
+            json!({"enabled": false, "mode": "advanced", "level": 3})
+        )?,
+        vec!["enabled", "always"]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_values() -> Result<()> {
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "enabled",
+                "type": "Checkbox",
+                "label": "Enabled",
+            },
+            {
+                "id": "mode",
+                "type": "text",
+                "label": "Mode",
+                "showIf": {"===": [{"var": "enabled"}, true]},
+            },
+            {
+                "id": "level",
+                "type": "number",
+                "label": "Level",
+                "showIfAll": [
+                    {
+                        "field": "mode",
+                        "value": "advanced",
+                    }
+                ],
+            },
+        ],
+        "outputs": {
+            "values": [
+                {"path": ["mode"], "value": {"FieldValue": {"input": "mode"}}},
+                {"path": ["level"], "value": {"FieldValue": {"input": "level"}}},
+            ],
+            "secrets": {
+                "config": {
+                    "level": {"FieldValue": {"input": "level"}},
+                },
+            },
+        },
+    }))?;
+
+    let inputs = json!({"enabled": true, "mode": "advanced", "level": 3});
+    let values: serde_json::Value = ui_schema
+        .get_values::<TestDb>(Uuid::new_v4(), &inputs)
+        .await?
+        .into();
+    assert_eq!(values, json!({"mode": "advanced", "level": 3}));
+    assert_eq!(
+        ui_schema
+            .get_secrets::<TestDb>(Uuid::new_v4(), &inputs)
+            .await?
+            .len(),
+        1
+    );
+
+    // level's own condition holds, but it depends on the hidden mode
+    let inputs = json!({"enabled": false, "mode": "advanced", "level": 3});
+    let values: serde_json::Value = ui_schema
+        .get_values::<TestDb>(Uuid::new_v4(), &inputs)
+        .await?
+        .into();
+    assert_eq!(values, json!({}));
+    assert!(ui_schema
+        .get_secrets::<TestDb>(Uuid::new_v4(), &inputs)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_errors() -> Result<()> {
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "name",
+                "type": "text",
+                "label": "Name",
+            },
+            {
+                "id": "not_bool",
+                "type": "text",
+                "label": "Not bool",
+                "showIf": {"var": "name"},
+            },
+        ],
+        "outputs": {
+            "values": [
+                {"path": ["name"], "value": {"FieldValue": {"input": "name"}}},
+                {"path": ["notBool"], "value": {"FieldValue": {"input": "not_bool"}}},
+            ],
+        },
+    }))?;
+    let inputs = json!({"name": "blah", "not_bool": "x"});
+    assert!(matches!(
+        ui_schema.visible_inputs(&inputs),
+        Err(UiSchemaVisibilityError::ShowIfNotBool(id, value))
+            if id == "not_bool" && value == "blah"
+    ));
+    // Rendering treats the input as hidden instead of failing
+    let values: serde_json::Value = ui_schema
+        .get_values::<TestDb>(Uuid::new_v4(), &inputs)
+        .await?
+        .into();
+    assert_eq!(values, json!({"name": "blah"}));
+
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "bad",
+                "type": "text",
+                "label": "Bad",
+                "showIf": {"no_such_operator": [1, 2]},
+            },
+        ],
+    }))?;
+    assert!(matches!(
+        ui_schema.visible_inputs(&json!({})),
+        Err(UiSchemaVisibilityError::ShowIfError(id, _)) if id == "bad"
+    ));
+
+    // Hiding the input makes it visible again and vice versa. The
+    // variable name is computed, so this isn't found as a cycle.
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "flip",
+                "type": "Checkbox",
+                "label": "Flip",
+                "showIf": {"!": [{"var": {"cat": ["fl", "ip"]}}]},
+            },
+        ],
+    }))?;
+    assert!(matches!(
+        ui_schema.visible_inputs(&json!({"flip": true})),
+        Err(UiSchemaVisibilityError::NotConverging)
+    ));
+    assert!(ui_schema
+        .get_values::<TestDb>(Uuid::new_v4(), &json!({"flip": true}))
+        .await
+        .is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_analysis() -> Result<()> {
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "enabled",
+                "type": "Checkbox",
+                "label": "Enabled",
+            },
+            {
+                "id": "mode",
+                "type": "text",
+                "label": "Mode",
+                "showIf": {"and": [{"var": "enabled"}, {"var": ["missing.field", false]}]},
+            },
+            {
+                "id": "first",
+                "type": "text",
+                "label": "First",
+                "showIf": {"!!": {"var": "second"}},
+            },
+            {
+                "id": "second",
+                "type": "text",
+                "label": "Second",
+                "showIfAll": [
+                    {
+                        "field": "first",
+                        "value": "yes",
+                    }
+                ],
+            },
+            {
+                "id": "disabled",
+                "type": "text",
+                "label": "Disabled",
+                "showIf": false,
+            },
+            {
+                "id": "dependent",
+                "type": "text",
+                "label": "Dependent",
+                "showIf": {"or": [{"var": "disabled"}, {"var": "first"}]},
+            },
+        ],
+    }))?;
+
+    assert_eq!(
+        ui_schema.get_inputs()[5].visibility_dependencies(),
+        vec!["disabled", "first"]
+    );
+    assert_eq!(
+        ui_schema.analyze_visibility(),
+        vec![
+            UiSchemaVisibilityIssue::UnknownVariable {
+                input: "mode".to_owned(),
+                variable: "missing".to_owned(),
+            },
+            UiSchemaVisibilityIssue::DependencyCycle(vec!["first".to_owned(), "second".to_owned()]),
+            UiSchemaVisibilityIssue::NeverVisible("disabled".to_owned()),
+            UiSchemaVisibilityIssue::DependsOnNeverVisible {
+                input: "dependent".to_owned(),
+                dependency: "disabled".to_owned(),
+            },
+            UiSchemaVisibilityIssue::DependsOnNeverVisible {
+                input: "dependent".to_owned(),
+                dependency: "first".to_owned(),
+            },
+        ]
+    );
+    assert!(matches!(
+        ui_schema.validate::<TestDb>(),
+        Err(UiSchemaValidationError::Visibility(
+            UiSchemaVisibilityIssue::UnknownVariable { .. }
+        ))
+    ));
+
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "itself",
+                "type": "text",
+                "label": "Itself",
+                "showIf": {"==": [{"var": "itself"}, "x"]},
+            },
+        ],
+    }))?;
+    assert_eq!(
+        ui_schema.analyze_visibility(),
+        vec![UiSchemaVisibilityIssue::DependencyCycle(vec![
+            "itself".to_owned()
+        ])]
+    );
+    // Never visible, even though its condition holds
+    assert!(visible_ids(&ui_schema, json!({"itself": "x"}))?.is_empty());
+
+    // Only reported as unknown, not as constant
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "prod_only",
+                "type": "text",
+                "label": "Prod only",
+                "showIf": {"==": [{"var": "env.name"}, "prod"]},
+            },
+        ],
+    }))?;
+    assert_eq!(
+        ui_schema.analyze_visibility(),
+        vec![UiSchemaVisibilityIssue::UnknownVariable {
+            input: "prod_only".to_owned(),
+            variable: "env".to_owned(),
+        }]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cycle_values() -> Result<()> {
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "itself",
+                "type": "text",
+                "label": "Itself",
+                "showIf": {"==": [{"var": "itself"}, "x"]},
+            },
+        ],
+        "outputs": {
+            "values": [
+                {"path": ["itself"], "value": {"FieldValue": {"input": "itself"}}},
+            ],
+        },
+    }))?;
+    // Inputs in a cycle are hidden, whatever their conditions evaluate to
+    let values: serde_json::Value = ui_schema
+        .get_values::<TestDb>(Uuid::new_v4(), &json!({"itself": "x"}))
+        .await?
+        .into();
+    assert_eq!(values, json!({}));
+    assert!(ui_schema
+        .visible_inputs(&json!({"itself": "x"}))?
+        .is_empty());
+
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {
+                "id": "a",
+                "type": "text",
+                "label": "A",
+                "showIf": {"==": [{"var": "b"}, "1"]},
+            },
+            {
+                "id": "b",
+                "type": "text",
+                "label": "B",
+                "showIf": {"==": [{"var": "a"}, "1"]},
+            },
+        ],
+        "outputs": {
+            "values": [
+                {"path": ["a"], "value": {"FieldValue": {"input": "a"}}},
+                {"path": ["b"], "value": {"FieldValue": {"input": "b"}}},
+            ],
+        },
+    }))?;
+    let inputs = json!({"a": "1", "b": "1"});
+    let values: serde_json::Value = ui_schema
+        .get_values::<TestDb>(Uuid::new_v4(), &inputs)
+        .await?
+        .into();
+    assert_eq!(values, json!({}));
+    assert!(ui_schema.visible_inputs(&inputs)?.is_empty());
+    assert_eq!(ui_schema.normalize_inputs(&inputs)?, json!({}));
+
+    Ok(())
+}
+
+#[test]
+fn test_dependencies() -> Result<()> {
+    let ui_schema: UiSchema = serde_json::from_value(json!({
+        "inputs": [
+            {"id": "tags", "type": "array", "itemType": "text", "label": "Tags"},
+            {"id": "name", "type": "text", "label": "Name"},
+            {"id": "owner", "type": "text", "label": "Owner"},
+            {"id": "email", "type": "text", "label": "Email"},
+            {"id": "limit", "type": "number", "label": "Limit"},
+            {
+                "id": "missing",
+                "type": "text",
+                "label": "Missing",
+                "showIf": {"or": [
+                    {"missing": ["name", "owner.id"]},
+                    {"missing_some": [1, ["email"]]},
+                ]},
+            },
+            {
+                "id": "scoped",
+                "type": "text",
+                "label": "Scoped",
+                "showIf": {"some": [
+                    {"var": "tags"},
+                    {"or": [{"==": [{"var": ""}, "beta"]}, {"var": "enabled"}]},
+                ]},
+            },
+            {
+                "id": "reduced",
+                "type": "text",
+                "label": "Reduced",
+                "showIf": {"<": [
+                    {"reduce": [
+                        {"var": "tags"},
+                        {"+": [{"var": "accumulator"}, 1]},
+                        {"var": "limit"},
+                    ]},
+                    10,
+                ]},
+            },
+        ],
+    }))?;
+    let dependencies = |i: usize| ui_schema.get_inputs()[i].visibility_dependencies();
+    assert_eq!(dependencies(5), vec!["name", "owner", "email"]);
+    assert_eq!(dependencies(6), vec!["tags"]);
+    assert_eq!(dependencies(7), vec!["tags", "limit"]);
+    assert!(ui_schema.analyze_visibility().is_empty());
+
+    Ok(())
+}